@@ -26,4 +26,8 @@ pub enum RateLimitError {
     /// Queue limit has been exceeded.
     #[error("Queue limit exceeded")]
     QueueLimitExceeded,
+
+    /// The acquisition did not complete before the requested wait timeout elapsed.
+    #[error("Acquisition timed out waiting for permits")]
+    Timeout,
 }
@@ -1,9 +1,18 @@
 //! Rate limit lease types.
 
+use crate::core::metadata::MetadataName;
+use crate::core::outcome::Outcome;
 use std::any::Any;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
 use std::time::Duration;
 
+/// `reported_outcome` states, stored as a plain `u8` so it can live behind
+/// an `AtomicU8` instead of a `Mutex<Outcome>`.
+const OUTCOME_SUCCESS: u8 = 0;
+const OUTCOME_OVERLOAD: u8 = 1;
+const OUTCOME_IGNORE: u8 = 2;
+
 /// Represents the result of a rate limit acquisition attempt.
 ///
 /// A lease indicates whether permits were successfully acquired and may
@@ -12,6 +21,8 @@ pub struct RateLimitLease {
     is_acquired: bool,
     metadata: HashMap<String, Box<dyn Any + Send + Sync>>,
     on_drop: Option<Box<dyn FnOnce() + Send>>,
+    reported_outcome: Option<AtomicU8>,
+    on_outcome: Option<Box<dyn FnOnce(Outcome) + Send>>,
 }
 
 impl RateLimitLease {
@@ -21,6 +32,8 @@ impl RateLimitLease {
             is_acquired: true,
             metadata: HashMap::new(),
             on_drop: None,
+            reported_outcome: None,
+            on_outcome: None,
         }
     }
 
@@ -35,6 +48,60 @@ impl RateLimitLease {
             is_acquired: true,
             metadata: HashMap::new(),
             on_drop: Some(Box::new(cleanup)),
+            reported_outcome: None,
+            on_outcome: None,
+        }
+    }
+
+    /// Create a successful lease that reports an `Outcome` back to the
+    /// limiter once the guarded work finishes.
+    ///
+    /// The lease defaults to reporting `Outcome::Success` when dropped; call
+    /// `report_overload()` or `report_outcome(Outcome::Overload)` beforehand
+    /// to report `Outcome::Overload` instead, or
+    /// `report_outcome(Outcome::Ignore)` to leave the limiter's state
+    /// untouched entirely. Used by adaptive limiters (e.g.
+    /// `AimdConcurrencyLimiter`) that adjust their permit limit from this
+    /// feedback.
+    pub fn success_with_outcome<F>(report: F) -> Self
+    where
+        F: FnOnce(Outcome) + Send + 'static,
+    {
+        Self {
+            is_acquired: true,
+            metadata: HashMap::new(),
+            on_drop: None,
+            reported_outcome: Some(AtomicU8::new(OUTCOME_SUCCESS)),
+            on_outcome: Some(Box::new(report)),
+        }
+    }
+
+    /// Mark this lease as having observed an overload signal (timeout,
+    /// 429/503, explicit backpressure) while it was held.
+    ///
+    /// Only meaningful on a lease created via `success_with_outcome`; has no
+    /// effect otherwise. Safe to call multiple times before the lease drops.
+    /// Equivalent to `report_outcome(Outcome::Overload)`.
+    pub fn report_overload(&self) {
+        self.report_outcome(Outcome::Overload);
+    }
+
+    /// Explicitly report the `Outcome` this lease's guarded work produced.
+    ///
+    /// Only meaningful on a lease created via `success_with_outcome`; has no
+    /// effect otherwise. Safe to call multiple times before the lease drops
+    /// - the most recent call wins. Callers that hit a non-congestion error
+    /// (e.g. a 4xx validation failure) should report `Outcome::Ignore` so
+    /// the limiter's permit limit is left untouched rather than being
+    /// nudged by an outcome that says nothing about capacity.
+    pub fn report_outcome(&self, outcome: Outcome) {
+        if let Some(reported) = &self.reported_outcome {
+            let value = match outcome {
+                Outcome::Success => OUTCOME_SUCCESS,
+                Outcome::Overload => OUTCOME_OVERLOAD,
+                Outcome::Ignore => OUTCOME_IGNORE,
+            };
+            reported.store(value, Ordering::Relaxed);
         }
     }
 
@@ -43,7 +110,7 @@ impl RateLimitLease {
         let mut metadata = HashMap::new();
         if let Some(duration) = retry_after {
             metadata.insert(
-                "RetryAfter".to_string(),
+                MetadataName::RETRY_AFTER.to_string(),
                 Box::new(duration) as Box<dyn Any + Send + Sync>,
             );
         }
@@ -52,6 +119,8 @@ impl RateLimitLease {
             is_acquired: false,
             metadata,
             on_drop: None,
+            reported_outcome: None,
+            on_outcome: None,
         }
     }
 
@@ -72,6 +141,16 @@ impl RateLimitLease {
         self.metadata.keys()
     }
 
+    /// Get the suggested retry-after duration, if the lease carries one.
+    ///
+    /// This is a typed shortcut for
+    /// `try_get_metadata::<Duration>(MetadataName::RETRY_AFTER)`, which is
+    /// how limiters attach retry hints to failed leases (see `Self::failed`).
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.try_get_metadata::<Duration>(MetadataName::RETRY_AFTER)
+            .copied()
+    }
+
     /// Add metadata to the lease.
     pub fn with_metadata<T: Any + Send + Sync + 'static>(
         mut self,
@@ -88,6 +167,17 @@ impl Drop for RateLimitLease {
         if let Some(cleanup) = self.on_drop.take() {
             cleanup();
         }
+        if let Some(report) = self.on_outcome.take() {
+            let outcome = match &self.reported_outcome {
+                Some(reported) => match reported.load(Ordering::Relaxed) {
+                    OUTCOME_OVERLOAD => Outcome::Overload,
+                    OUTCOME_IGNORE => Outcome::Ignore,
+                    _ => Outcome::Success,
+                },
+                None => Outcome::Success,
+            };
+            report(outcome);
+        }
     }
 }
 
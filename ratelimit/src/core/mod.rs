@@ -9,6 +9,7 @@
 pub mod error;
 pub mod lease;
 pub mod metadata;
+pub mod outcome;
 pub mod statistics;
 pub mod traits;
 
@@ -16,6 +17,7 @@ pub mod traits;
 pub use error::RateLimitError;
 pub use lease::RateLimitLease;
 pub use metadata::MetadataName;
+pub use outcome::Outcome;
 pub use statistics::RateLimiterStatistics;
 pub use traits::{RateLimiter, ReplenishingRateLimiter};
 
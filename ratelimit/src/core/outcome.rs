@@ -0,0 +1,19 @@
+//! Outcome feedback for self-tuning rate limiters.
+
+/// Feedback signal reported back through a lease once the work it guarded
+/// has finished, used by adaptive limiters (e.g. `AimdConcurrencyLimiter`)
+/// that adjust their permit limit from observed behavior rather than a
+/// fixed constant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The guarded request completed without any sign of overload (no
+    /// timeout, no 429/503, no explicit backpressure from the callee).
+    Success,
+    /// The caller observed an overload signal while the lease was held.
+    Overload,
+    /// The guarded request failed for a reason unrelated to congestion
+    /// (e.g. a 4xx validation error, a client bug). The limit should be
+    /// left exactly as it was - neither evidence of spare capacity nor of
+    /// overload.
+    Ignore,
+}
@@ -1,5 +1,7 @@
 //! Rate limiter statistics types.
 
+use std::time::Duration;
+
 /// A snapshot of rate limiter statistics at a point in time.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RateLimiterStatistics {
@@ -9,15 +11,45 @@ pub struct RateLimiterStatistics {
     /// Number of permits currently in the queue waiting for availability.
     pub current_queued_count: u32,
 
+    /// Number of requests currently parked in the queue waiting for
+    /// availability, as a request count rather than a permit count.
+    ///
+    /// This differs from `current_queued_count` when queued requests ask for
+    /// more than one permit each: a single waiting request can account for
+    /// many queued permits.
+    pub current_waiting_count: u32,
+
     /// Total number of successful lease acquisitions since limiter creation.
     pub total_successful_leases: u64,
 
     /// Total number of failed lease acquisitions since limiter creation.
     pub total_failed_leases: u64,
+
+    /// Total number of leases that were granted only after waiting in the
+    /// queue (i.e. excludes leases granted immediately on acquisition).
+    pub queued_lease_count: u64,
+
+    /// Sum of the queue wait time across all leases counted by
+    /// `queued_lease_count`. Divide by that count to get the mean.
+    pub total_queue_wait_time: Duration,
+
+    /// The longest queue wait time observed among leases counted by
+    /// `queued_lease_count`.
+    pub max_queue_wait_time: Duration,
+
+    /// Total replenishment tokens discarded because the bucket was already
+    /// at (or would have exceeded) its configured capacity, monotonically
+    /// increasing since limiter creation. A nonzero, growing value means
+    /// `tokens_per_period` is provisioned above what demand actually
+    /// consumes. Always zero for limiters that don't replenish.
+    pub dropped_permits: u64,
 }
 
 impl RateLimiterStatistics {
-    /// Create a new statistics snapshot.
+    /// Create a new statistics snapshot with no queue wait-time data.
+    ///
+    /// Use this for limiters or call sites that don't track per-lease queue
+    /// latency; the wait-time fields are left at zero.
     pub fn new(
         current_available_permits: i64,
         current_queued_count: u32,
@@ -27,8 +59,23 @@ impl RateLimiterStatistics {
         Self {
             current_available_permits,
             current_queued_count,
+            current_waiting_count: 0,
             total_successful_leases,
             total_failed_leases,
+            queued_lease_count: 0,
+            total_queue_wait_time: Duration::ZERO,
+            max_queue_wait_time: Duration::ZERO,
+            dropped_permits: 0,
+        }
+    }
+
+    /// Mean queue wait time across all leases that had to wait, or `None` if
+    /// none have been recorded yet.
+    pub fn mean_queue_wait_time(&self) -> Option<Duration> {
+        if self.queued_lease_count == 0 {
+            None
+        } else {
+            Some(self.total_queue_wait_time / self.queued_lease_count as u32)
         }
     }
 }
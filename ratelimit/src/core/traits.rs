@@ -56,6 +56,42 @@ pub trait RateLimiter: Send + Sync {
         cancel_token: Option<CancellationToken>,
     ) -> Result<RateLimitLease, RateLimitError>;
 
+    /// Acquire permits asynchronously, bounded by a maximum wait timeout.
+    ///
+    /// Behaves exactly like `acquire_async`, except that if `timeout` elapses
+    /// before permits are granted, the pending request is cancelled (freeing
+    /// its queue slot the same way an externally cancelled request would) and
+    /// the future resolves with `RateLimitError::Timeout` instead of blocking
+    /// indefinitely. If the caller's own `cancel_token` fires first, the
+    /// result is `RateLimitError::Cancelled` as usual.
+    ///
+    /// This has a default implementation in terms of `acquire_async` and does
+    /// not need to be overridden by most implementors.
+    async fn acquire_async_timeout(
+        &self,
+        permit_count: u32,
+        cancel_token: Option<CancellationToken>,
+        timeout: Duration,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        let effective_cancel = cancel_token.unwrap_or_default();
+        let future = self.acquire_async(permit_count, Some(effective_cancel.clone()));
+        tokio::pin!(future);
+
+        tokio::select! {
+            result = &mut future => result,
+            _ = tokio::time::sleep(timeout) => {
+                // Cancel the in-flight acquisition so the limiter frees the
+                // queue slot it reserved, then drive it to completion so that
+                // cleanup actually runs before we return.
+                effective_cancel.cancel();
+                match future.await {
+                    Err(RateLimitError::Cancelled) => Err(RateLimitError::Timeout),
+                    other => other,
+                }
+            }
+        }
+    }
+
     /// Get a snapshot of the current rate limiter statistics.
     ///
     /// Returns current state including available permits, queue count,
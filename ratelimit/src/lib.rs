@@ -21,12 +21,16 @@ pub mod core;
 pub mod limiters;
 pub mod partitioned;
 pub mod queue;
+/// Optional Tower `Service`/`Layer` adapter, gated by the `tower` feature.
+#[cfg(feature = "tower")]
+pub mod tower;
 pub mod utils;
 
 // Re-export commonly used types
 pub use core::{
     error::RateLimitError,
     lease::RateLimitLease,
+    outcome::Outcome,
     statistics::RateLimiterStatistics,
     traits::{RateLimiter, ReplenishingRateLimiter},
     QueueProcessingOrder,
@@ -34,18 +38,34 @@ pub use core::{
 
 // Re-export limiter implementations
 pub use limiters::{
-    ChainedRateLimiter,
+    AimdConcurrencyLimiter, AimdConcurrencyLimiterOptions,
+    ChainPolicy, ChainedRateLimiter,
+    CiadBehavior, CiadClassification, CiadLimiter, CiadLimiterOptions,
+    CompositeTokenBucketLimiter, CompositeTokenBucketLimiterOptions,
     ConcurrencyLimiter, ConcurrencyLimiterOptions,
-    FixedWindowRateLimiter, FixedWindowRateLimiterOptions,
+    FixedWindowRateLimiter, FixedWindowRateLimiterOptions, RateLimitReservation,
+    FreezingLimiter,
+    LeakyBucketRateLimiter, LeakyBucketRateLimiterOptions,
+    BucketConfig, MultiBucketRateLimiter,
+    MultiTokenBucketLimiter, MultiTokenBucketLimiterOptions, TokenType,
+    ResponsiveTokenBucketLimiter, ResponsiveTokenBucketLimiterOptions,
+    ServerFeedbackLimiter, RateLimitHeaders,
     SlidingWindowRateLimiter, SlidingWindowRateLimiterOptions,
-    TokenBucketRateLimiter, TokenBucketRateLimiterOptions,
+    BucketUpdate, TokenBucketRateLimiter, TokenBucketRateLimiterOptions,
 };
 
 // Re-export partitioned limiters
 pub use partitioned::{
     PartitionedRateLimiter,
+    CombinedPartitionedRateLimiter,
+    KeyedTokenBucketRateLimiter,
+    ServerFeedbackPartitionedLimiter,
     create_per_key_token_bucket,
     create_per_key_concurrency,
     create_per_key_fixed_window,
     create_per_key_sliding_window,
+    create_per_key_multi_bucket,
 };
+
+// Re-export supporting utilities
+pub use utils::{CachedClock, Clock, ManualClock, SystemClock, TokioClock};
@@ -0,0 +1,447 @@
+//! AIMD-based adaptive concurrency limiter.
+//!
+//! Unlike the fixed-limit limiters in this module, `AimdConcurrencyLimiter` does
+//! not enforce a fixed permit limit. It starts from a configured estimate
+//! and adjusts it up or down based on `Outcome` feedback reported through
+//! the leases it grants, converging toward the real capacity of whatever
+//! downstream resource it's protecting instead of relying on a hand-picked
+//! constant.
+//!
+//! `current_limit()` and `outcome_counts()` expose the adaptive state
+//! directly for callers (dashboards, logging) that want more detail than
+//! `get_statistics()`'s fixed `RateLimiterStatistics` shape provides.
+
+use crate::core::outcome::Outcome;
+use crate::core::traits::RateLimiter;
+use crate::core::{RateLimitError, RateLimitLease, RateLimiterStatistics};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+/// Options for configuring an `AimdConcurrencyLimiter`.
+#[derive(Clone, Debug)]
+pub struct AimdConcurrencyLimiterOptions {
+    /// Starting value for the adaptive limit.
+    pub initial_limit: f64,
+
+    /// Lower bound the adaptive limit is clamped to.
+    pub min_limit: f64,
+
+    /// Upper bound the adaptive limit is clamped to.
+    pub max_limit: f64,
+
+    /// Additive increase numerator applied on `Outcome::Success` while
+    /// saturated: `limit += increase / limit`.
+    pub increase: f64,
+
+    /// Multiplicative decrease factor applied on `Outcome::Overload`:
+    /// `limit *= decrease_factor`.
+    pub decrease_factor: f64,
+}
+
+impl AimdConcurrencyLimiterOptions {
+    /// Create new AIMD options with validation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `min_limit` is not positive, `max_limit` is less
+    /// than `min_limit`, `initial_limit` falls outside `[min_limit,
+    /// max_limit]`, `increase` is not positive, or `decrease_factor` is not
+    /// in `(0, 1)`.
+    pub fn new(
+        initial_limit: f64,
+        min_limit: f64,
+        max_limit: f64,
+        increase: f64,
+        decrease_factor: f64,
+    ) -> Result<Self, RateLimitError> {
+        if min_limit <= 0.0 {
+            return Err(RateLimitError::InvalidParameter(
+                "min_limit must be greater than 0".to_string(),
+            ));
+        }
+        if max_limit < min_limit {
+            return Err(RateLimitError::InvalidParameter(
+                "max_limit must be greater than or equal to min_limit".to_string(),
+            ));
+        }
+        if initial_limit < min_limit || initial_limit > max_limit {
+            return Err(RateLimitError::InvalidParameter(
+                "initial_limit must fall within [min_limit, max_limit]".to_string(),
+            ));
+        }
+        if increase <= 0.0 {
+            return Err(RateLimitError::InvalidParameter(
+                "increase must be greater than 0".to_string(),
+            ));
+        }
+        if !(decrease_factor > 0.0 && decrease_factor < 1.0) {
+            return Err(RateLimitError::InvalidParameter(
+                "decrease_factor must be in the range (0, 1)".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            initial_limit,
+            min_limit,
+            max_limit,
+            increase,
+            decrease_factor,
+        })
+    }
+}
+
+/// Internal state for the AIMD limiter.
+struct State {
+    /// The current adaptive limit, clamped to `[min_limit, max_limit]`.
+    limit: f64,
+
+    /// Number of permits currently in flight.
+    in_flight: u32,
+
+    /// Time when the limiter became idle (no permits in flight).
+    idle_since: Option<Instant>,
+}
+
+/// A rate limiter whose permit limit adapts to observed success/overload
+/// feedback instead of staying fixed.
+///
+/// A lease is granted when `in_flight < floor(limit)`. `attempt_acquire`
+/// fails immediately if no slot is free; `acquire_async` instead waits on a
+/// `tokio::sync::Notify` until one opens up or the cancellation token fires.
+/// The caller reports how the guarded work went by calling
+/// `RateLimitLease::report_overload()` before dropping the lease (or simply
+/// dropping it, for the success case). On success while saturated, the
+/// limit grows additively; on overload, it shrinks multiplicatively. This
+/// only supports acquiring a single permit per call, since the limit tracks
+/// concurrent in-flight requests rather than an arbitrary permit budget.
+pub struct AimdConcurrencyLimiter {
+    state: Arc<Mutex<State>>,
+    config: AimdConcurrencyLimiterOptions,
+    successful_leases: Arc<AtomicU64>,
+    failed_leases: Arc<AtomicU64>,
+    /// Count of leases reported as `Outcome::Success`, independent of
+    /// `successful_leases` (which counts acquisitions, not how the work the
+    /// lease guarded actually turned out).
+    success_outcomes: Arc<AtomicU64>,
+    /// Count of leases reported as `Outcome::Overload`.
+    overload_outcomes: Arc<AtomicU64>,
+    /// Wakes `acquire_async` waiters whenever a lease is dropped and a slot
+    /// may have opened up.
+    notify: Arc<Notify>,
+}
+
+impl AimdConcurrencyLimiter {
+    /// Create a new AIMD concurrency limiter with the specified options.
+    pub fn new(options: AimdConcurrencyLimiterOptions) -> Self {
+        let state = State {
+            limit: options.initial_limit,
+            in_flight: 0,
+            idle_since: Some(Instant::now()),
+        };
+
+        Self {
+            state: Arc::new(Mutex::new(state)),
+            config: options,
+            successful_leases: Arc::new(AtomicU64::new(0)),
+            failed_leases: Arc::new(AtomicU64::new(0)),
+            success_outcomes: Arc::new(AtomicU64::new(0)),
+            overload_outcomes: Arc::new(AtomicU64::new(0)),
+            notify: Arc::new(Notify::new()),
+        }
+    }
+
+    /// The current computed limit, before flooring to a whole permit count.
+    pub fn current_limit(&self) -> f64 {
+        self.state.lock().limit
+    }
+
+    /// Recent outcome feedback as `(success_count, overload_count)`, counted
+    /// since the limiter was created.
+    pub fn outcome_counts(&self) -> (u64, u64) {
+        (
+            self.success_outcomes.load(Ordering::Relaxed),
+            self.overload_outcomes.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Apply outcome feedback once the lease that produced it is dropped.
+    fn on_outcome(
+        state: &Mutex<State>,
+        config: &AimdConcurrencyLimiterOptions,
+        success_outcomes: &AtomicU64,
+        overload_outcomes: &AtomicU64,
+        outcome: Outcome,
+    ) {
+        let mut s = state.lock();
+        s.in_flight = s.in_flight.saturating_sub(1);
+
+        match outcome {
+            Outcome::Success => {
+                success_outcomes.fetch_add(1, Ordering::Relaxed);
+                // Only grow the limit if we were actually saturated just
+                // before this request completed; otherwise an unrelated
+                // success with capacity to spare would inflate the limit
+                // for no reason.
+                if (s.in_flight as f64) + 1.0 >= s.limit.floor() {
+                    s.limit = (s.limit + config.increase / s.limit).min(config.max_limit);
+                }
+            }
+            Outcome::Overload => {
+                overload_outcomes.fetch_add(1, Ordering::Relaxed);
+                s.limit = (s.limit * config.decrease_factor).max(config.min_limit);
+            }
+            // A non-congestion failure says nothing about capacity - leave
+            // the limit exactly where it was, and don't count it among the
+            // success/overload outcomes either.
+            Outcome::Ignore => {}
+        }
+
+        if s.in_flight == 0 {
+            s.idle_since = Some(Instant::now());
+        }
+    }
+
+    /// Grants a lease immediately if a slot is free, bumping `in_flight` and
+    /// wiring up outcome reporting (including waking any `acquire_async`
+    /// waiters once the lease is eventually dropped). Returns `None` if the
+    /// limiter is currently saturated.
+    fn try_grant(&self) -> Option<RateLimitLease> {
+        let mut state = self.state.lock();
+
+        if (state.in_flight as f64) >= state.limit.floor() {
+            return None;
+        }
+
+        state.in_flight += 1;
+        state.idle_since = None;
+        self.successful_leases.fetch_add(1, Ordering::Relaxed);
+
+        let state_handle = Arc::clone(&self.state);
+        let config = self.config.clone();
+        let success_outcomes = Arc::clone(&self.success_outcomes);
+        let overload_outcomes = Arc::clone(&self.overload_outcomes);
+        let notify = Arc::clone(&self.notify);
+        Some(RateLimitLease::success_with_outcome(move |outcome| {
+            Self::on_outcome(
+                &state_handle,
+                &config,
+                &success_outcomes,
+                &overload_outcomes,
+                outcome,
+            );
+            notify.notify_waiters();
+        }))
+    }
+}
+
+#[async_trait]
+impl RateLimiter for AimdConcurrencyLimiter {
+    fn attempt_acquire(&self, permit_count: u32) -> Result<RateLimitLease, RateLimitError> {
+        if permit_count > 1 {
+            return Err(RateLimitError::InvalidParameter(
+                "AimdConcurrencyLimiter only supports acquiring a single permit at a time"
+                    .to_string(),
+            ));
+        }
+        if permit_count == 0 {
+            return Ok(RateLimitLease::success());
+        }
+
+        if let Some(lease) = self.try_grant() {
+            return Ok(lease);
+        }
+
+        self.failed_leases.fetch_add(1, Ordering::Relaxed);
+        Ok(RateLimitLease::failed(None))
+    }
+
+    async fn acquire_async(
+        &self,
+        permit_count: u32,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        if permit_count > 1 {
+            return Err(RateLimitError::InvalidParameter(
+                "AimdConcurrencyLimiter only supports acquiring a single permit at a time"
+                    .to_string(),
+            ));
+        }
+        if permit_count == 0 {
+            return Ok(RateLimitLease::success());
+        }
+
+        let cancel = cancel_token.unwrap_or_default();
+
+        loop {
+            // Subscribe before checking state, so a wakeup that lands
+            // between the check and the await below isn't missed.
+            let notified = self.notify.notified();
+
+            if let Some(lease) = self.try_grant() {
+                return Ok(lease);
+            }
+
+            tokio::pin!(notified);
+            tokio::select! {
+                _ = &mut notified => {}
+                _ = cancel.cancelled() => return Err(RateLimitError::Cancelled),
+            }
+        }
+    }
+
+    fn get_statistics(&self) -> RateLimiterStatistics {
+        let state = self.state.lock();
+        let available = (state.limit.floor() as i64 - state.in_flight as i64).max(0);
+
+        RateLimiterStatistics::new(
+            available,
+            0,
+            self.successful_leases.load(Ordering::Relaxed),
+            self.failed_leases.load(Ordering::Relaxed),
+        )
+    }
+
+    fn idle_duration(&self) -> Option<Duration> {
+        let state = self.state.lock();
+        state.idle_since.map(|since| since.elapsed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grants_up_to_floor_of_initial_limit() {
+        let options = AimdConcurrencyLimiterOptions::new(2.0, 1.0, 10.0, 1.0, 0.5).unwrap();
+        let limiter = AimdConcurrencyLimiter::new(options);
+
+        let lease1 = limiter.attempt_acquire(1).unwrap();
+        assert!(lease1.is_acquired());
+        let lease2 = limiter.attempt_acquire(1).unwrap();
+        assert!(lease2.is_acquired());
+        let lease3 = limiter.attempt_acquire(1).unwrap();
+        assert!(!lease3.is_acquired());
+    }
+
+    #[test]
+    fn test_success_while_saturated_increases_limit() {
+        let options = AimdConcurrencyLimiterOptions::new(2.0, 1.0, 10.0, 1.0, 0.5).unwrap();
+        let limiter = AimdConcurrencyLimiter::new(options);
+
+        let lease1 = limiter.attempt_acquire(1).unwrap();
+        let _lease2 = limiter.attempt_acquire(1).unwrap();
+        assert_eq!(limiter.current_limit(), 2.0);
+
+        // Reporting success while saturated (2 in flight, limit floor is 2)
+        // should grow the limit additively.
+        drop(lease1);
+        assert!(limiter.current_limit() > 2.0);
+    }
+
+    #[test]
+    fn test_overload_decreases_limit_multiplicatively() {
+        let options = AimdConcurrencyLimiterOptions::new(4.0, 1.0, 10.0, 1.0, 0.5).unwrap();
+        let limiter = AimdConcurrencyLimiter::new(options);
+
+        let lease = limiter.attempt_acquire(1).unwrap();
+        lease.report_overload();
+        drop(lease);
+
+        assert_eq!(limiter.current_limit(), 2.0);
+    }
+
+    #[test]
+    fn test_decrease_clamps_to_min_limit() {
+        let options = AimdConcurrencyLimiterOptions::new(1.0, 1.0, 10.0, 1.0, 0.1).unwrap();
+        let limiter = AimdConcurrencyLimiter::new(options);
+
+        let lease = limiter.attempt_acquire(1).unwrap();
+        lease.report_overload();
+        drop(lease);
+
+        assert_eq!(limiter.current_limit(), 1.0);
+    }
+
+    #[test]
+    fn test_outcome_counts_track_success_and_overload_separately() {
+        let options = AimdConcurrencyLimiterOptions::new(2.0, 1.0, 10.0, 1.0, 0.5).unwrap();
+        let limiter = AimdConcurrencyLimiter::new(options);
+
+        let lease1 = limiter.attempt_acquire(1).unwrap();
+        drop(lease1); // implicit success
+        assert_eq!(limiter.outcome_counts(), (1, 0));
+
+        let lease2 = limiter.attempt_acquire(1).unwrap();
+        lease2.report_overload();
+        drop(lease2);
+        assert_eq!(limiter.outcome_counts(), (1, 1));
+    }
+
+    #[test]
+    fn test_ignored_outcome_leaves_limit_and_counts_untouched() {
+        let options = AimdConcurrencyLimiterOptions::new(2.0, 1.0, 10.0, 1.0, 0.5).unwrap();
+        let limiter = AimdConcurrencyLimiter::new(options);
+
+        // Saturate the limiter so a Success would normally grow the limit.
+        let lease1 = limiter.attempt_acquire(1).unwrap();
+        let lease2 = limiter.attempt_acquire(1).unwrap();
+
+        lease1.report_outcome(Outcome::Ignore);
+        drop(lease1);
+
+        assert_eq!(limiter.current_limit(), 2.0);
+        assert_eq!(limiter.outcome_counts(), (0, 0));
+
+        drop(lease2);
+    }
+
+    #[test]
+    fn test_rejects_multi_permit_requests() {
+        let options = AimdConcurrencyLimiterOptions::new(4.0, 1.0, 10.0, 1.0, 0.5).unwrap();
+        let limiter = AimdConcurrencyLimiter::new(options);
+
+        assert!(limiter.attempt_acquire(2).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_acquire_async_waits_for_a_freed_slot() {
+        let options = AimdConcurrencyLimiterOptions::new(1.0, 1.0, 10.0, 1.0, 0.5).unwrap();
+        let limiter = Arc::new(AimdConcurrencyLimiter::new(options));
+
+        let lease1 = limiter.acquire_async(1, None).await.unwrap();
+        assert!(lease1.is_acquired());
+
+        // The only slot is taken, so this waits until `lease1` is dropped.
+        let waiter = {
+            let limiter = Arc::clone(&limiter);
+            tokio::spawn(async move { limiter.acquire_async(1, None).await })
+        };
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        drop(lease1);
+        let lease2 = waiter.await.unwrap().unwrap();
+        assert!(lease2.is_acquired());
+    }
+
+    #[tokio::test]
+    async fn test_acquire_async_cancels_while_waiting() {
+        let options = AimdConcurrencyLimiterOptions::new(1.0, 1.0, 10.0, 1.0, 0.5).unwrap();
+        let limiter = AimdConcurrencyLimiter::new(options);
+
+        let _lease1 = limiter.acquire_async(1, None).await.unwrap();
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let result = limiter.acquire_async(1, Some(cancel)).await;
+        assert_eq!(result, Err(RateLimitError::Cancelled));
+    }
+}
@@ -1,6 +1,7 @@
 //! Chained rate limiter implementation.
 //!
-//! Combines multiple rate limiters in sequence where ALL must approve before granting access.
+//! Combines multiple rate limiters according to a `ChainPolicy`: `All` (every
+//! limiter must approve) or `Any` (the first limiter to approve wins).
 
 use crate::core::{RateLimitError, RateLimitLease, RateLimiter, RateLimiterStatistics};
 use async_trait::async_trait;
@@ -8,6 +9,31 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio_util::sync::CancellationToken;
 
+/// How a `ChainedRateLimiter` combines the outcomes of its inner limiters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainPolicy {
+    /// Every limiter in the chain must approve before a lease is granted.
+    /// If any fails, permits already acquired from earlier limiters are
+    /// released. This is the default, and `ChainedRateLimiter::new`'s
+    /// original behavior.
+    All,
+    /// A lease is granted as soon as any limiter in the chain approves,
+    /// leaving the others untouched. Useful for tiered quotas - e.g.
+    /// approve if either a per-user burst bucket or a shared spillover pool
+    /// has capacity.
+    ///
+    /// `acquire_async` tries limiters in order, one at a time, rather than
+    /// racing them concurrently - a consume-on-grant limiter (e.g.
+    /// `TokenBucketRateLimiter`) never refunds permits on lease drop, so
+    /// racing would permanently debit any "losing" limiter that happened to
+    /// grant before a faster one won. The tradeoff is latency, not
+    /// correctness: a slow or queuing limiter earlier in the chain is
+    /// awaited to completion before a later, immediately-available limiter
+    /// is even tried. Put limiters most likely to have capacity first if
+    /// tail latency matters.
+    Any,
+}
+
 /// A rate limiter that chains multiple limiters together.
 ///
 /// All limiters must successfully acquire permits for the overall acquisition to succeed.
@@ -32,6 +58,10 @@ use tokio_util::sync::CancellationToken;
 ///     auto_replenishment: true,
 ///     queue_limit: 0,
 ///     queue_processing_order: QueueProcessingOrder::OldestFirst,
+///     one_time_burst: 0,
+///     replenish_fractionally: false,
+///     clock: Arc::new(ratelimit::utils::SystemClock),
+///     ready_threshold: 1,
 /// };
 /// let user_limiter = Arc::new(TokenBucketRateLimiter::new(user_options).unwrap());
 ///
@@ -53,10 +83,13 @@ use tokio_util::sync::CancellationToken;
 pub struct ChainedRateLimiter {
     /// The ordered list of limiters to check
     limiters: Vec<Arc<dyn RateLimiter>>,
+    /// How the limiters' outcomes are combined.
+    policy: ChainPolicy,
 }
 
 impl ChainedRateLimiter {
-    /// Create a new chained rate limiter.
+    /// Create a new chained rate limiter with `ChainPolicy::All` - every
+    /// limiter must approve before a lease is granted.
     ///
     /// # Arguments
     ///
@@ -70,83 +103,154 @@ impl ChainedRateLimiter {
     ///
     /// * `InvalidParameter` - If the limiters vector is empty
     pub fn new(limiters: Vec<Arc<dyn RateLimiter>>) -> Result<Self, RateLimitError> {
+        Self::with_policy(limiters, ChainPolicy::All)
+    }
+
+    /// Create a new chained rate limiter with an explicit combine policy.
+    ///
+    /// # Errors
+    ///
+    /// * `InvalidParameter` - If the limiters vector is empty
+    pub fn with_policy(
+        limiters: Vec<Arc<dyn RateLimiter>>,
+        policy: ChainPolicy,
+    ) -> Result<Self, RateLimitError> {
         if limiters.is_empty() {
             return Err(RateLimitError::InvalidParameter(
                 "Must provide at least 1 limiter".to_string(),
             ));
         }
 
-        Ok(Self { limiters })
+        Ok(Self { limiters, policy })
     }
 
     /// Get the number of limiters in the chain.
     pub fn limiter_count(&self) -> usize {
         self.limiters.len()
     }
-}
 
-#[async_trait]
-impl RateLimiter for ChainedRateLimiter {
-    fn attempt_acquire(&self, permit_count: u32) -> Result<RateLimitLease, RateLimitError> {
+    /// The combine policy this chain was constructed with.
+    pub fn policy(&self) -> ChainPolicy {
+        self.policy
+    }
+
+    fn attempt_acquire_all(&self, permit_count: u32) -> Result<RateLimitLease, RateLimitError> {
         let mut acquired_leases = Vec::with_capacity(self.limiters.len());
 
-        // Try to acquire from each limiter in order
         for (index, limiter) in self.limiters.iter().enumerate() {
             match limiter.attempt_acquire(permit_count) {
                 Ok(lease) if lease.is_acquired() => {
                     acquired_leases.push(lease);
                 }
                 Ok(lease) => {
-                    // Failed lease - clean up and return first failure with metadata
-                    drop(acquired_leases); // Release all previously acquired
-
-                    // Add metadata about which limiter failed
+                    drop(acquired_leases);
                     return Ok(lease.with_metadata("FailedLimiterIndex", index));
                 }
                 Err(e) => {
-                    // Error - clean up and propagate
-                    drop(acquired_leases); // Release all previously acquired
+                    drop(acquired_leases);
                     return Err(e);
                 }
             }
         }
 
-        // All succeeded - return combined lease
         Ok(CombinedLease::create(acquired_leases))
     }
 
-    async fn acquire_async(
+    fn attempt_acquire_any(&self, permit_count: u32) -> Result<RateLimitLease, RateLimitError> {
+        let mut failed_indices = Vec::new();
+
+        for (index, limiter) in self.limiters.iter().enumerate() {
+            // A hard error (e.g. `PermitCountExceeded`) means this limiter
+            // can't satisfy the request, not that the whole chain can't -
+            // a later, larger-capacity limiter might still grant it.
+            match limiter.attempt_acquire(permit_count) {
+                Ok(lease) if lease.is_acquired() => return Ok(lease),
+                Ok(_) | Err(_) => failed_indices.push(index),
+            }
+        }
+
+        Ok(RateLimitLease::failed(None).with_metadata("FailedLimiterIndex", failed_indices))
+    }
+
+    async fn acquire_async_all(
         &self,
         permit_count: u32,
         cancel_token: Option<CancellationToken>,
     ) -> Result<RateLimitLease, RateLimitError> {
         let mut acquired_leases = Vec::with_capacity(self.limiters.len());
 
-        // Try to acquire from each limiter in order
         for (index, limiter) in self.limiters.iter().enumerate() {
             match limiter.acquire_async(permit_count, cancel_token.clone()).await {
                 Ok(lease) if lease.is_acquired() => {
                     acquired_leases.push(lease);
                 }
                 Ok(lease) => {
-                    // Failed lease - clean up and return first failure
-                    drop(acquired_leases); // Release all previously acquired
-
-                    // Add metadata about which limiter failed
+                    drop(acquired_leases);
                     return Ok(lease.with_metadata("FailedLimiterIndex", index));
                 }
                 Err(e) => {
-                    // Error - clean up and propagate
-                    drop(acquired_leases); // Release all previously acquired
+                    drop(acquired_leases);
                     return Err(e);
                 }
             }
         }
 
-        // All succeeded - return combined lease
         Ok(CombinedLease::create(acquired_leases))
     }
 
+    /// Tries each limiter's `acquire_async` in turn and returns the first to
+    /// grant a lease, leaving the rest untouched.
+    ///
+    /// This is sequential, not raced concurrently: a consume-on-grant
+    /// limiter like `TokenBucketRateLimiter` debits its tokens the moment it
+    /// grants, and never gives them back when a lease is merely dropped, so
+    /// racing every limiter at once and cancelling the "losers" would
+    /// permanently leak permits from any eager limiter that granted before
+    /// losing the race. Only fails once every limiter has failed, with
+    /// `FailedLimiterIndex` metadata listing every limiter index that didn't
+    /// grant a lease.
+    async fn acquire_async_any(
+        &self,
+        permit_count: u32,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        let mut failed_indices = Vec::new();
+
+        for (index, limiter) in self.limiters.iter().enumerate() {
+            // A hard error from one limiter doesn't rule out a later one;
+            // only a cancellation aborts the whole search immediately.
+            match limiter.acquire_async(permit_count, cancel_token.clone()).await {
+                Ok(lease) if lease.is_acquired() => return Ok(lease),
+                Ok(_) => failed_indices.push(index),
+                Err(RateLimitError::Cancelled) => return Err(RateLimitError::Cancelled),
+                Err(_) => failed_indices.push(index),
+            }
+        }
+
+        Ok(RateLimitLease::failed(None).with_metadata("FailedLimiterIndex", failed_indices))
+    }
+}
+
+#[async_trait]
+impl RateLimiter for ChainedRateLimiter {
+    fn attempt_acquire(&self, permit_count: u32) -> Result<RateLimitLease, RateLimitError> {
+        match self.policy {
+            ChainPolicy::All => self.attempt_acquire_all(permit_count),
+            ChainPolicy::Any => self.attempt_acquire_any(permit_count),
+        }
+    }
+
+    async fn acquire_async(
+        &self,
+        permit_count: u32,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        match self.policy {
+            ChainPolicy::All => self.acquire_async_all(permit_count, cancel_token).await,
+            ChainPolicy::Any => self.acquire_async_any(permit_count, cancel_token).await,
+        }
+    }
+
     fn get_statistics(&self) -> RateLimiterStatistics {
         if self.limiters.is_empty() {
             return RateLimiterStatistics::new(0, 0, 0, 0);
@@ -159,44 +263,73 @@ impl RateLimiter for ChainedRateLimiter {
         for limiter in &self.limiters[1..] {
             let stats = limiter.get_statistics();
 
-            // Take minimum of available permits (bottleneck)
-            combined_stats.current_available_permits = combined_stats
-                .current_available_permits
-                .min(stats.current_available_permits);
+            // Under `All`, every limiter must grant, so the chain's capacity
+            // is bottlenecked by whichever has the fewest permits. Under
+            // `Any`, only one needs to grant, so the chain's capacity is as
+            // good as its roomiest option.
+            combined_stats.current_available_permits = match self.policy {
+                ChainPolicy::All => combined_stats
+                    .current_available_permits
+                    .min(stats.current_available_permits),
+                ChainPolicy::Any => combined_stats
+                    .current_available_permits
+                    .max(stats.current_available_permits),
+            };
 
             // Take maximum of queued counts (worst case)
             combined_stats.current_queued_count = combined_stats
                 .current_queued_count
                 .max(stats.current_queued_count);
 
+            // Take maximum of waiting counts (worst case)
+            combined_stats.current_waiting_count = combined_stats
+                .current_waiting_count
+                .max(stats.current_waiting_count);
+
             // Sum successes and failures (all contribute)
             combined_stats.total_successful_leases += stats.total_successful_leases;
             combined_stats.total_failed_leases += stats.total_failed_leases;
+
+            // Sum queue wait metrics across the chain and keep the longest max.
+            combined_stats.queued_lease_count += stats.queued_lease_count;
+            combined_stats.total_queue_wait_time += stats.total_queue_wait_time;
+            combined_stats.max_queue_wait_time =
+                combined_stats.max_queue_wait_time.max(stats.max_queue_wait_time);
         }
 
         combined_stats
     }
 
     fn idle_duration(&self) -> Option<Duration> {
-        // We're idle if ALL limiters are idle
-        let mut min_idle_duration: Option<Duration> = None;
-
-        for limiter in &self.limiters {
-            match limiter.idle_duration() {
-                Some(duration) => {
-                    min_idle_duration = Some(match min_idle_duration {
-                        Some(min) => min.min(duration),
-                        None => duration,
-                    });
-                }
-                None => {
-                    // At least one limiter is not idle
-                    return None;
+        match self.policy {
+            // Under `All`, using the chain draws on every limiter at once,
+            // so it's only idle once every one of them is.
+            ChainPolicy::All => {
+                let mut min_idle_duration: Option<Duration> = None;
+
+                for limiter in &self.limiters {
+                    match limiter.idle_duration() {
+                        Some(duration) => {
+                            min_idle_duration = Some(match min_idle_duration {
+                                Some(min) => min.min(duration),
+                                None => duration,
+                            });
+                        }
+                        None => return None,
+                    }
                 }
+
+                min_idle_duration
             }
+            // Under `Any`, a single idle limiter is enough capacity to
+            // satisfy the chain, so it's idle as soon as any one is -
+            // reported as whichever has been idle the longest.
+            ChainPolicy::Any => self
+                .limiters
+                .iter()
+                .filter_map(|limiter| limiter.idle_duration())
+                .max(),
         }
-
-        min_idle_duration
     }
 }
 
@@ -261,6 +394,10 @@ mod tests {
             auto_replenishment: false,
             queue_limit: 0,
             queue_processing_order: QueueProcessingOrder::OldestFirst,
+            one_time_burst: 0,
+            replenish_fractionally: false,
+            clock: Arc::new(crate::utils::SystemClock),
+            ready_threshold: 1,
         };
         let limiter2 = Arc::new(TokenBucketRateLimiter::new(token_options).unwrap());
 
@@ -424,4 +561,162 @@ mod tests {
         let result = acquire_task.await.unwrap();
         assert!(matches!(result, Err(RateLimitError::Cancelled)));
     }
+
+    fn exhausted_limiter() -> Arc<ConcurrencyLimiter> {
+        let options = ConcurrencyLimiterOptions {
+            permit_limit: 1,
+            queue_limit: 0,
+            queue_processing_order: QueueProcessingOrder::OldestFirst,
+        };
+        let limiter = Arc::new(ConcurrencyLimiter::new(options).unwrap());
+        // Keep the only permit held after this helper returns; dropping the
+        // lease would hand it back and leave the limiter with capacity.
+        std::mem::forget(limiter.attempt_acquire(1).unwrap());
+        limiter
+    }
+
+    fn roomy_limiter(permit_limit: u32) -> Arc<ConcurrencyLimiter> {
+        let options = ConcurrencyLimiterOptions {
+            permit_limit,
+            queue_limit: 0,
+            queue_processing_order: QueueProcessingOrder::OldestFirst,
+        };
+        Arc::new(ConcurrencyLimiter::new(options).unwrap())
+    }
+
+    #[test]
+    fn test_any_policy_grants_if_any_limiter_approves() {
+        let exhausted = exhausted_limiter() as Arc<dyn RateLimiter>;
+        let roomy = roomy_limiter(5) as Arc<dyn RateLimiter>;
+
+        let chained =
+            ChainedRateLimiter::with_policy(vec![exhausted, roomy], ChainPolicy::Any).unwrap();
+
+        let lease = chained.attempt_acquire(1).unwrap();
+        assert!(lease.is_acquired());
+    }
+
+    #[test]
+    fn test_any_policy_fails_only_when_all_fail() {
+        let exhausted1 = exhausted_limiter() as Arc<dyn RateLimiter>;
+        let exhausted2 = exhausted_limiter() as Arc<dyn RateLimiter>;
+
+        let chained = ChainedRateLimiter::with_policy(vec![exhausted1, exhausted2], ChainPolicy::Any)
+            .unwrap();
+
+        let lease = chained.attempt_acquire(1).unwrap();
+        assert!(!lease.is_acquired());
+
+        let failed_indices = lease.try_get_metadata::<Vec<usize>>("FailedLimiterIndex");
+        assert_eq!(failed_indices, Some(&vec![0, 1]));
+    }
+
+    #[test]
+    fn test_any_policy_statistics_take_the_maximum() {
+        let tight = roomy_limiter(2) as Arc<dyn RateLimiter>;
+        let roomy = roomy_limiter(10) as Arc<dyn RateLimiter>;
+
+        let chained = ChainedRateLimiter::with_policy(vec![tight, roomy], ChainPolicy::Any).unwrap();
+
+        let stats = chained.get_statistics();
+        assert_eq!(stats.current_available_permits, 10);
+    }
+
+    #[tokio::test]
+    async fn test_any_policy_acquire_async_returns_first_success() {
+        let exhausted = exhausted_limiter() as Arc<dyn RateLimiter>;
+        let roomy = roomy_limiter(5) as Arc<dyn RateLimiter>;
+
+        let chained =
+            ChainedRateLimiter::with_policy(vec![exhausted, roomy], ChainPolicy::Any).unwrap();
+
+        let lease = chained.acquire_async(1, None).await.unwrap();
+        assert!(lease.is_acquired());
+    }
+
+    #[tokio::test]
+    async fn test_any_policy_acquire_async_cancellation_propagates() {
+        // Both limiters queue indefinitely (permit exhausted, queue open),
+        // so the only way this resolves is via the caller's cancellation.
+        let options = ConcurrencyLimiterOptions {
+            permit_limit: 1,
+            queue_limit: 10,
+            queue_processing_order: QueueProcessingOrder::OldestFirst,
+        };
+        let limiter1 = Arc::new(ConcurrencyLimiter::new(options.clone()).unwrap());
+        let _lease1 = limiter1.attempt_acquire(1).unwrap();
+        let limiter2 = Arc::new(ConcurrencyLimiter::new(options).unwrap());
+        let _lease2 = limiter2.attempt_acquire(1).unwrap();
+
+        let chained = ChainedRateLimiter::with_policy(
+            vec![limiter1 as Arc<dyn RateLimiter>, limiter2 as Arc<dyn RateLimiter>],
+            ChainPolicy::Any,
+        )
+        .unwrap();
+
+        let cancel_token = CancellationToken::new();
+        let cancel_clone = cancel_token.clone();
+        let acquire_task = tokio::spawn(async move { chained.acquire_async(1, Some(cancel_clone)).await });
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        cancel_token.cancel();
+
+        let result = acquire_task.await.unwrap();
+        assert!(matches!(result, Err(RateLimitError::Cancelled)));
+    }
+
+    fn token_bucket_limiter(token_limit: u32) -> Arc<TokenBucketRateLimiter> {
+        let options = TokenBucketRateLimiterOptions {
+            tokens_per_period: token_limit,
+            token_limit,
+            replenishment_period: Duration::from_secs(1),
+            auto_replenishment: false,
+            queue_limit: 0,
+            queue_processing_order: QueueProcessingOrder::OldestFirst,
+            one_time_burst: 0,
+            replenish_fractionally: false,
+            clock: Arc::new(crate::utils::SystemClock),
+            ready_threshold: 1,
+        };
+        Arc::new(TokenBucketRateLimiter::new(options).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_any_policy_acquire_async_does_not_debit_the_losing_bucket() {
+        // Both buckets have room, so under the old concurrent race both
+        // would grant (debiting tokens) before the first-success winner was
+        // picked; the loser's tokens never come back since token buckets
+        // don't refund on lease drop. Sequential acquisition must never
+        // touch the second limiter once the first has already granted.
+        let first = token_bucket_limiter(5);
+        let second = token_bucket_limiter(5);
+        let chained = ChainedRateLimiter::with_policy(
+            vec![
+                first.clone() as Arc<dyn RateLimiter>,
+                second.clone() as Arc<dyn RateLimiter>,
+            ],
+            ChainPolicy::Any,
+        )
+        .unwrap();
+
+        let lease = chained.acquire_async(1, None).await.unwrap();
+        assert!(lease.is_acquired());
+        assert_eq!(first.available_tokens(), 4.0);
+        assert_eq!(second.available_tokens(), 5.0);
+    }
+
+    #[test]
+    fn test_any_policy_skips_a_limiter_that_hard_errors() {
+        // A chain of [small-cap, large-cap] under Any must still serve a
+        // request too big for the first limiter's `permit_count` ceiling -
+        // its `PermitCountExceeded` only rules out that limiter, not the
+        // whole chain.
+        let small = roomy_limiter(1) as Arc<dyn RateLimiter>;
+        let large = roomy_limiter(10) as Arc<dyn RateLimiter>;
+
+        let chained = ChainedRateLimiter::with_policy(vec![small, large], ChainPolicy::Any).unwrap();
+
+        let lease = chained.attempt_acquire(5).unwrap();
+        assert!(lease.is_acquired());
+    }
 }
\ No newline at end of file
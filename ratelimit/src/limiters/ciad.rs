@@ -0,0 +1,377 @@
+//! CIAD ("cautious increase, aggressive decrease") concurrency limiter.
+//!
+//! Complements `AimdConcurrencyLimiter`: instead of additive-increase /
+//! multiplicative-decrease in response to a binary success/overload signal,
+//! `CiadLimiter` grows its limit very cautiously on success (one extra slot
+//! per full round-trip through the current window) and shrinks it
+//! aggressively the moment a congestion signal is observed, biasing the
+//! limiter toward backing off fast and probing capacity slowly.
+
+use crate::core::traits::RateLimiter;
+use crate::core::{RateLimitError, RateLimitLease, RateLimiterStatistics};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+/// How a downstream response should be classified for CIAD feedback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CiadClassification {
+    /// Doesn't count either way (e.g. a client error unrelated to load).
+    Ignore,
+    /// A normal completion with no sign of congestion.
+    Success,
+    /// A congestion signal (e.g. an HTTP 429/503-equivalent, or explicit
+    /// overload backpressure from the callee).
+    Dropped,
+}
+
+/// Maps a caller's own response or error type onto a `CiadClassification`.
+///
+/// Kept as a trait object so applications can plug in whatever status/error
+/// type their downstream call returns without `CiadLimiter` needing to know
+/// about it.
+pub trait CiadBehavior<T>: Send + Sync {
+    /// Classify a completed response for CIAD feedback.
+    fn classify(&self, response: &T) -> CiadClassification;
+}
+
+/// Options for configuring a `CiadLimiter`.
+#[derive(Clone, Debug)]
+pub struct CiadLimiterOptions {
+    /// Starting value for the adaptive limit.
+    pub initial_limit: f64,
+
+    /// Lower bound the adaptive limit is clamped to.
+    pub min_limit: f64,
+
+    /// Upper bound the adaptive limit is clamped to.
+    pub max_limit: f64,
+}
+
+impl CiadLimiterOptions {
+    /// Create new CIAD options with validation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `min_limit` is not positive, `max_limit` is less
+    /// than `min_limit`, or `initial_limit` falls outside `[min_limit,
+    /// max_limit]`.
+    pub fn new(initial_limit: f64, min_limit: f64, max_limit: f64) -> Result<Self, RateLimitError> {
+        if min_limit <= 0.0 {
+            return Err(RateLimitError::InvalidParameter(
+                "min_limit must be greater than 0".to_string(),
+            ));
+        }
+        if max_limit < min_limit {
+            return Err(RateLimitError::InvalidParameter(
+                "max_limit must be greater than or equal to min_limit".to_string(),
+            ));
+        }
+        if initial_limit < min_limit || initial_limit > max_limit {
+            return Err(RateLimitError::InvalidParameter(
+                "initial_limit must fall within [min_limit, max_limit]".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            initial_limit,
+            min_limit,
+            max_limit,
+        })
+    }
+}
+
+impl Default for CiadLimiterOptions {
+    fn default() -> Self {
+        Self {
+            initial_limit: 20.0,
+            min_limit: 1.0,
+            max_limit: 1_000_000.0,
+        }
+    }
+}
+
+/// Internal in-flight/idle tracking, separate from the atomic limit so the
+/// cautious-increase/aggressive-decrease math never needs the same lock
+/// that `attempt_acquire`/`acquire_async` contend on.
+struct State {
+    in_flight: u32,
+    idle_since: Option<Instant>,
+}
+
+/// A concurrency limiter that grows its limit cautiously on success and
+/// shrinks it aggressively on congestion, using a pluggable `CiadBehavior`
+/// to classify each completed response.
+///
+/// `T` is the caller's own response or error type; construct with a
+/// `CiadBehavior<T>` that maps it onto a `CiadClassification`, then call
+/// `record_response` once each guarded call completes. A lease is granted
+/// when `in_flight < floor(limit)`; `acquire_async` queues on a `Notify`
+/// until a slot frees instead of failing immediately.
+pub struct CiadLimiter<T> {
+    limit_bits: Arc<AtomicU64>,
+    min_limit: f64,
+    max_limit: f64,
+    state: Arc<Mutex<State>>,
+    successful_leases: AtomicU64,
+    failed_leases: AtomicU64,
+    notify: Arc<Notify>,
+    behavior: Arc<dyn CiadBehavior<T>>,
+}
+
+impl<T> CiadLimiter<T> {
+    /// Create a new CIAD limiter with the default options (initial limit
+    /// 20, clamped to `[1, 1_000_000]`).
+    pub fn new(behavior: Arc<dyn CiadBehavior<T>>) -> Self {
+        Self::with_options(behavior, CiadLimiterOptions::default())
+    }
+
+    /// Create a new CIAD limiter with the specified options.
+    pub fn with_options(behavior: Arc<dyn CiadBehavior<T>>, options: CiadLimiterOptions) -> Self {
+        Self {
+            limit_bits: Arc::new(AtomicU64::new(options.initial_limit.to_bits())),
+            min_limit: options.min_limit,
+            max_limit: options.max_limit,
+            state: Arc::new(Mutex::new(State {
+                in_flight: 0,
+                idle_since: Some(Instant::now()),
+            })),
+            successful_leases: AtomicU64::new(0),
+            failed_leases: AtomicU64::new(0),
+            notify: Arc::new(Notify::new()),
+            behavior,
+        }
+    }
+
+    /// The current adaptive limit, before flooring to a whole permit count.
+    pub fn current_limit(&self) -> f64 {
+        f64::from_bits(self.limit_bits.load(Ordering::Relaxed))
+    }
+
+    /// Classify `response` via the configured `CiadBehavior` and apply the
+    /// resulting cautious-increase/aggressive-decrease feedback to the
+    /// limit.
+    ///
+    /// This is independent of lease lifetime: call it once a guarded call
+    /// completes, alongside (not instead of) dropping its lease.
+    pub fn record_response(&self, response: &T) {
+        match self.behavior.classify(response) {
+            CiadClassification::Ignore => {}
+            CiadClassification::Success => self.record_classification(CiadClassification::Success),
+            CiadClassification::Dropped => self.record_classification(CiadClassification::Dropped),
+        }
+    }
+
+    /// Applies a classification directly, bypassing `CiadBehavior`. Exposed
+    /// for callers that have already classified the response themselves.
+    pub fn record_classification(&self, classification: CiadClassification) {
+        match classification {
+            CiadClassification::Ignore => {}
+            CiadClassification::Success => self.apply(|limit| limit + 1.0 / limit),
+            CiadClassification::Dropped => self.apply(|limit| limit * 0.9),
+        }
+    }
+
+    /// Atomically updates the limit with `f`, clamped to `[min_limit,
+    /// max_limit]`, retrying on concurrent updates.
+    fn apply(&self, f: impl Fn(f64) -> f64) {
+        loop {
+            let bits = self.limit_bits.load(Ordering::Relaxed);
+            let current = f64::from_bits(bits);
+            let next = f(current).clamp(self.min_limit, self.max_limit);
+            if self
+                .limit_bits
+                .compare_exchange_weak(bits, next.to_bits(), Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+            {
+                return;
+            }
+        }
+    }
+
+    /// Grants a lease immediately if a slot is free, bumping `in_flight` and
+    /// wiring up cleanup that frees the slot and wakes `acquire_async`
+    /// waiters when the lease is dropped. Returns `None` if saturated.
+    fn try_grant(&self) -> Option<RateLimitLease> {
+        let mut state = self.state.lock();
+
+        if (state.in_flight as f64) >= self.current_limit().floor() {
+            return None;
+        }
+
+        state.in_flight += 1;
+        state.idle_since = None;
+        self.successful_leases.fetch_add(1, Ordering::Relaxed);
+
+        let state_handle = Arc::clone(&self.state);
+        let notify = Arc::clone(&self.notify);
+        Some(RateLimitLease::success_with_cleanup(move || {
+            let mut s = state_handle.lock();
+            s.in_flight = s.in_flight.saturating_sub(1);
+            if s.in_flight == 0 {
+                s.idle_since = Some(Instant::now());
+            }
+            drop(s);
+            notify.notify_waiters();
+        }))
+    }
+}
+
+#[async_trait]
+impl<T: Send + Sync + 'static> RateLimiter for CiadLimiter<T> {
+    fn attempt_acquire(&self, permit_count: u32) -> Result<RateLimitLease, RateLimitError> {
+        if permit_count > 1 {
+            return Err(RateLimitError::InvalidParameter(
+                "CiadLimiter only supports acquiring a single permit at a time".to_string(),
+            ));
+        }
+        if permit_count == 0 {
+            return Ok(RateLimitLease::success());
+        }
+
+        if let Some(lease) = self.try_grant() {
+            return Ok(lease);
+        }
+
+        self.failed_leases.fetch_add(1, Ordering::Relaxed);
+        Ok(RateLimitLease::failed(None))
+    }
+
+    async fn acquire_async(
+        &self,
+        permit_count: u32,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        if permit_count > 1 {
+            return Err(RateLimitError::InvalidParameter(
+                "CiadLimiter only supports acquiring a single permit at a time".to_string(),
+            ));
+        }
+        if permit_count == 0 {
+            return Ok(RateLimitLease::success());
+        }
+
+        let cancel = cancel_token.unwrap_or_default();
+
+        loop {
+            let notified = self.notify.notified();
+
+            if let Some(lease) = self.try_grant() {
+                return Ok(lease);
+            }
+
+            tokio::pin!(notified);
+            tokio::select! {
+                _ = &mut notified => {}
+                _ = cancel.cancelled() => return Err(RateLimitError::Cancelled),
+            }
+        }
+    }
+
+    fn get_statistics(&self) -> RateLimiterStatistics {
+        let state = self.state.lock();
+        let available = (self.current_limit().floor() as i64 - state.in_flight as i64).max(0);
+
+        RateLimiterStatistics::new(
+            available,
+            0,
+            self.successful_leases.load(Ordering::Relaxed),
+            self.failed_leases.load(Ordering::Relaxed),
+        )
+    }
+
+    fn idle_duration(&self) -> Option<Duration> {
+        let state = self.state.lock();
+        state.idle_since.map(|since| since.elapsed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct HttpStatusBehavior;
+
+    impl CiadBehavior<u16> for HttpStatusBehavior {
+        fn classify(&self, status: &u16) -> CiadClassification {
+            match status {
+                200..=299 => CiadClassification::Success,
+                429 | 503 => CiadClassification::Dropped,
+                _ => CiadClassification::Ignore,
+            }
+        }
+    }
+
+    fn limiter(initial: f64, min: f64, max: f64) -> CiadLimiter<u16> {
+        let options = CiadLimiterOptions::new(initial, min, max).unwrap();
+        CiadLimiter::with_options(Arc::new(HttpStatusBehavior), options)
+    }
+
+    #[test]
+    fn test_grants_up_to_floor_of_initial_limit() {
+        let limiter = limiter(2.0, 1.0, 10.0);
+
+        let lease1 = limiter.attempt_acquire(1).unwrap();
+        assert!(lease1.is_acquired());
+        let lease2 = limiter.attempt_acquire(1).unwrap();
+        assert!(lease2.is_acquired());
+        let lease3 = limiter.attempt_acquire(1).unwrap();
+        assert!(!lease3.is_acquired());
+    }
+
+    #[test]
+    fn test_success_increases_limit_cautiously() {
+        let limiter = limiter(2.0, 1.0, 10.0);
+
+        limiter.record_response(&200);
+        assert_eq!(limiter.current_limit(), 2.0 + 1.0 / 2.0);
+    }
+
+    #[test]
+    fn test_dropped_decreases_limit_aggressively() {
+        let limiter = limiter(4.0, 1.0, 10.0);
+
+        limiter.record_response(&429);
+        assert_eq!(limiter.current_limit(), 3.6);
+    }
+
+    #[test]
+    fn test_ignored_status_leaves_limit_unchanged() {
+        let limiter = limiter(4.0, 1.0, 10.0);
+
+        limiter.record_response(&404);
+        assert_eq!(limiter.current_limit(), 4.0);
+    }
+
+    #[test]
+    fn test_decrease_clamps_to_min_limit() {
+        let limiter = limiter(1.0, 1.0, 10.0);
+
+        limiter.record_response(&503);
+        assert_eq!(limiter.current_limit(), 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_acquire_async_waits_for_a_freed_slot() {
+        let limiter = Arc::new(limiter(1.0, 1.0, 10.0));
+
+        let lease1 = limiter.acquire_async(1, None).await.unwrap();
+        assert!(lease1.is_acquired());
+
+        let waiter = {
+            let limiter = Arc::clone(&limiter);
+            tokio::spawn(async move { limiter.acquire_async(1, None).await })
+        };
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        drop(lease1);
+        let lease2 = waiter.await.unwrap().unwrap();
+        assert!(lease2.is_acquired());
+    }
+}
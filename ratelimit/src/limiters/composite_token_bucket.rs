@@ -0,0 +1,499 @@
+//! Composite N-dimension token-bucket limiter with queueing.
+//!
+//! Complements `MultiBucketRateLimiter`'s fail-fast named buckets with
+//! queueing: following the cloud-hypervisor/Firecracker model of independent
+//! `bandwidth` and `ops` buckets, `CompositeTokenBucketLimiter` wraps any
+//! number of named token-bucket dimensions and grants a lease only when
+//! *every* dimension can afford its share of the request's cost atomically
+//! -- if any dimension is short, none are debited. Unlike
+//! `MultiBucketRateLimiter::acquire_async`, which fails immediately rather
+//! than waiting, `acquire_async_typed` queues the request until all
+//! dimensions are simultaneously satisfiable, and a failed `attempt_acquire`
+//! reports the longest of the per-dimension waits as `RetryAfter` metadata.
+
+use crate::core::traits::RateLimiter;
+use crate::core::{QueueProcessingOrder, RateLimitError, RateLimitLease, RateLimiterStatistics};
+use crate::limiters::multi_bucket::BucketConfig;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+
+/// Options for configuring a `CompositeTokenBucketLimiter`.
+#[derive(Clone, Debug)]
+pub struct CompositeTokenBucketLimiterOptions {
+    /// Bucket configuration for each named dimension (e.g. `"bandwidth"`,
+    /// `"ops"`).
+    pub buckets: HashMap<String, BucketConfig>,
+
+    /// Maximum total cost (summed across all dimensions) that can be queued.
+    pub queue_limit: u32,
+
+    /// Order in which queued requests are processed.
+    pub queue_processing_order: QueueProcessingOrder,
+}
+
+/// Runtime state for a single named dimension's bucket.
+struct BucketState {
+    available: f64,
+    last_replenished: Instant,
+}
+
+/// A queued request waiting for every dimension to have capacity.
+struct QueuedRequest {
+    costs: HashMap<String, u32>,
+    response: oneshot::Sender<Result<RateLimitLease, RateLimitError>>,
+    queued_at: Instant,
+}
+
+/// Internal state shared across all dimensions.
+struct State {
+    buckets: HashMap<String, BucketState>,
+    queue: VecDeque<QueuedRequest>,
+    queue_count: u32,
+    idle_since: Option<Instant>,
+    disposed: bool,
+}
+
+/// A rate limiter enforcing several independent named token budgets at once,
+/// granting a lease only when every dimension can afford the request's cost.
+///
+/// `attempt_acquire_typed` checks all dimensions immediately and fails
+/// without debiting any if one is short, tagging the failed lease with
+/// `RetryAfter` metadata set to the longest of the per-dimension waits.
+/// `acquire_async_typed` instead queues the request until every dimension
+/// can be satisfied together. The plain `RateLimiter` trait methods apply
+/// `permit_count` uniformly to every configured dimension, for callers that
+/// don't need independently sized costs.
+pub struct CompositeTokenBucketLimiter {
+    configs: HashMap<String, BucketConfig>,
+    queue_limit: u32,
+    queue_processing_order: QueueProcessingOrder,
+    state: Arc<Mutex<State>>,
+    successful_leases: Arc<AtomicU64>,
+    failed_leases: Arc<AtomicU64>,
+    replenish_cancel: CancellationToken,
+}
+
+impl CompositeTokenBucketLimiter {
+    /// Create a new composite token-bucket limiter with the specified
+    /// options.
+    pub fn new(options: CompositeTokenBucketLimiterOptions) -> Self {
+        let now = Instant::now();
+        let buckets = options
+            .buckets
+            .iter()
+            .map(|(name, cfg)| {
+                (
+                    name.clone(),
+                    BucketState {
+                        available: cfg.token_limit as f64,
+                        last_replenished: now,
+                    },
+                )
+            })
+            .collect();
+
+        Self {
+            configs: options.buckets,
+            queue_limit: options.queue_limit,
+            queue_processing_order: options.queue_processing_order,
+            state: Arc::new(Mutex::new(State {
+                buckets,
+                queue: VecDeque::new(),
+                queue_count: 0,
+                idle_since: Some(now),
+                disposed: false,
+            })),
+            successful_leases: Arc::new(AtomicU64::new(0)),
+            failed_leases: Arc::new(AtomicU64::new(0)),
+            replenish_cancel: CancellationToken::new(),
+        }
+    }
+
+    /// Spawns background tasks that replenish every named dimension on its
+    /// own independent period until this limiter is dropped.
+    pub fn start_auto_replenishment(self: &Arc<Self>) {
+        for name in self.configs.keys().cloned().collect::<Vec<_>>() {
+            let limiter = Arc::downgrade(self);
+            let period = self.configs[&name].replenishment_period;
+            let cancel = self.replenish_cancel.clone();
+
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(period);
+                loop {
+                    tokio::select! {
+                        _ = interval.tick() => match limiter.upgrade() {
+                            Some(limiter) => limiter.replenish(&name),
+                            None => break,
+                        },
+                        _ = cancel.cancelled() => break,
+                    }
+                }
+            });
+        }
+    }
+
+    /// Replenish one dimension's bucket and wake any queued requests that
+    /// can now be satisfied.
+    fn replenish(&self, name: &str) {
+        let mut state = self.state.lock();
+        if state.disposed {
+            return;
+        }
+
+        let Some(config) = self.configs.get(name) else {
+            return;
+        };
+        let now = Instant::now();
+        if let Some(bucket) = state.buckets.get_mut(name) {
+            let elapsed = now.duration_since(bucket.last_replenished);
+            let periods = elapsed.as_secs_f64() / config.replenishment_period.as_secs_f64();
+            let tokens_to_add = periods * config.tokens_per_period as f64;
+
+            if tokens_to_add > 0.0 {
+                bucket.available =
+                    (bucket.available + tokens_to_add).min(config.token_limit as f64);
+                bucket.last_replenished = now;
+            }
+        }
+
+        self.update_idle_locked(&mut state);
+        self.process_queue_locked(&mut state);
+    }
+
+    fn update_idle_locked(&self, state: &mut State) {
+        let all_full = self.configs.iter().all(|(name, cfg)| {
+            state
+                .buckets
+                .get(name)
+                .is_some_and(|bucket| bucket.available >= cfg.token_limit as f64 - 0.001)
+        });
+
+        if all_full && state.idle_since.is_none() {
+            state.idle_since = Some(Instant::now());
+        }
+    }
+
+    /// Whether `state`'s buckets can currently afford `costs` without
+    /// debiting anything.
+    fn can_afford(state: &State, costs: &HashMap<String, u32>) -> bool {
+        costs.iter().all(|(name, cost)| {
+            state
+                .buckets
+                .get(name)
+                .is_some_and(|bucket| bucket.available >= *cost as f64)
+        })
+    }
+
+    fn debit_locked(state: &mut State, costs: &HashMap<String, u32>) {
+        for (name, cost) in costs {
+            if let Some(bucket) = state.buckets.get_mut(name) {
+                bucket.available -= *cost as f64;
+            }
+        }
+    }
+
+    /// The longest wait, across every dimension named in `costs` that is
+    /// currently short, until that dimension alone could afford its share.
+    fn retry_after_locked(&self, state: &State, costs: &HashMap<String, u32>) -> Duration {
+        costs
+            .iter()
+            .filter_map(|(name, cost)| {
+                let config = self.configs.get(name)?;
+                let bucket = state.buckets.get(name)?;
+                let tokens_needed = (*cost as f64 - bucket.available).max(0.0);
+                if tokens_needed <= 0.0 {
+                    return None;
+                }
+                let periods_needed =
+                    (tokens_needed / config.tokens_per_period as f64).ceil() as u32;
+                Some(config.replenishment_period * periods_needed.max(1))
+            })
+            .max()
+            .unwrap_or(Duration::ZERO)
+    }
+
+    fn process_queue_locked(&self, state: &mut State) {
+        loop {
+            let next_index = match self.queue_processing_order {
+                QueueProcessingOrder::OldestFirst => 0,
+                QueueProcessingOrder::NewestFirst => state.queue.len().saturating_sub(1),
+            };
+
+            let Some(next) = state.queue.get(next_index) else {
+                break;
+            };
+
+            if next.response.is_closed() {
+                if let Some(req) = state.queue.remove(next_index) {
+                    state.queue_count -= req.costs.values().sum::<u32>();
+                }
+                continue;
+            }
+
+            if !Self::can_afford(state, &next.costs) {
+                break;
+            }
+
+            let req = state.queue.remove(next_index).unwrap();
+            state.queue_count -= req.costs.values().sum::<u32>();
+            Self::debit_locked(state, &req.costs);
+            state.idle_since = None;
+            let _ = req.response.send(Ok(RateLimitLease::success()));
+            self.successful_leases.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Attempt to acquire a per-dimension cost vector immediately, without
+    /// queueing. Succeeds only if every named dimension currently has enough
+    /// tokens; otherwise no bucket is debited and the returned lease fails,
+    /// carrying `RetryAfter` metadata set to the longest per-dimension wait.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidParameter` if `costs` names a dimension this limiter
+    /// was not configured with.
+    pub fn attempt_acquire_typed(
+        &self,
+        costs: &HashMap<String, u32>,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        for name in costs.keys() {
+            if !self.configs.contains_key(name) {
+                return Err(RateLimitError::InvalidParameter(format!(
+                    "unknown bucket '{name}'"
+                )));
+            }
+        }
+
+        let mut state = self.state.lock();
+        if state.disposed {
+            return Err(RateLimitError::Disposed);
+        }
+
+        if !Self::can_afford(&state, costs) {
+            self.failed_leases.fetch_add(1, Ordering::Relaxed);
+            let retry_after = self.retry_after_locked(&state, costs);
+            return Ok(RateLimitLease::failed(Some(retry_after)));
+        }
+
+        Self::debit_locked(&mut state, costs);
+        state.idle_since = None;
+        self.successful_leases.fetch_add(1, Ordering::Relaxed);
+        Ok(RateLimitLease::success())
+    }
+
+    /// Acquire a per-dimension cost vector, queueing until every dimension
+    /// can be satisfied together if any is currently short.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidParameter` if `costs` names a dimension this limiter
+    /// was not configured with.
+    pub async fn acquire_async_typed(
+        &self,
+        costs: HashMap<String, u32>,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        for name in costs.keys() {
+            if !self.configs.contains_key(name) {
+                return Err(RateLimitError::InvalidParameter(format!(
+                    "unknown bucket '{name}'"
+                )));
+            }
+        }
+
+        let rx = {
+            let mut state = self.state.lock();
+            if state.disposed {
+                return Err(RateLimitError::Disposed);
+            }
+
+            if Self::can_afford(&state, &costs) {
+                Self::debit_locked(&mut state, &costs);
+                state.idle_since = None;
+                self.successful_leases.fetch_add(1, Ordering::Relaxed);
+                return Ok(RateLimitLease::success());
+            }
+
+            let total_cost: u32 = costs.values().sum();
+            if state.queue_count + total_cost > self.queue_limit {
+                self.failed_leases.fetch_add(1, Ordering::Relaxed);
+                let retry_after = self.retry_after_locked(&state, &costs);
+                return Ok(RateLimitLease::failed(Some(retry_after)));
+            }
+
+            let (tx, rx) = oneshot::channel();
+            state.queue.push_back(QueuedRequest {
+                costs,
+                response: tx,
+                queued_at: Instant::now(),
+            });
+            state.queue_count += total_cost;
+            rx
+        };
+
+        if let Some(token) = cancel_token {
+            tokio::select! {
+                result = rx => result.unwrap_or(Err(RateLimitError::Cancelled)),
+                _ = token.cancelled() => Err(RateLimitError::Cancelled),
+            }
+        } else {
+            rx.await.unwrap_or(Err(RateLimitError::Cancelled))
+        }
+    }
+}
+
+#[async_trait]
+impl RateLimiter for CompositeTokenBucketLimiter {
+    fn attempt_acquire(&self, permit_count: u32) -> Result<RateLimitLease, RateLimitError> {
+        let costs: HashMap<String, u32> = self
+            .configs
+            .keys()
+            .map(|name| (name.clone(), permit_count))
+            .collect();
+        self.attempt_acquire_typed(&costs)
+    }
+
+    async fn acquire_async(
+        &self,
+        permit_count: u32,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        let costs: HashMap<String, u32> = self
+            .configs
+            .keys()
+            .map(|name| (name.clone(), permit_count))
+            .collect();
+        self.acquire_async_typed(costs, cancel_token).await
+    }
+
+    fn get_statistics(&self) -> RateLimiterStatistics {
+        let state = self.state.lock();
+        let available = state
+            .buckets
+            .values()
+            .map(|bucket| bucket.available as i64)
+            .min()
+            .unwrap_or(0);
+
+        RateLimiterStatistics::new(
+            available,
+            state.queue_count,
+            self.successful_leases.load(Ordering::Relaxed),
+            self.failed_leases.load(Ordering::Relaxed),
+        )
+    }
+
+    fn idle_duration(&self) -> Option<Duration> {
+        let state = self.state.lock();
+        state.idle_since.map(|since| since.elapsed())
+    }
+}
+
+impl Drop for CompositeTokenBucketLimiter {
+    fn drop(&mut self) {
+        self.replenish_cancel.cancel();
+
+        let mut state = self.state.lock();
+        state.disposed = true;
+        while let Some(request) = state.queue.pop_front() {
+            state.queue_count -= request.costs.values().sum::<u32>();
+            let _ = request.response.send(Ok(RateLimitLease::failed(None)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(bandwidth_limit: u32, ops_limit: u32, queue_limit: u32) -> CompositeTokenBucketLimiterOptions {
+        let mut buckets = HashMap::new();
+        buckets.insert(
+            "bandwidth".to_string(),
+            BucketConfig::new(bandwidth_limit, bandwidth_limit, Duration::from_secs(60)).unwrap(),
+        );
+        buckets.insert(
+            "ops".to_string(),
+            BucketConfig::new(ops_limit, ops_limit, Duration::from_secs(60)).unwrap(),
+        );
+        CompositeTokenBucketLimiterOptions {
+            buckets,
+            queue_limit,
+            queue_processing_order: QueueProcessingOrder::OldestFirst,
+        }
+    }
+
+    #[test]
+    fn test_acquires_when_every_dimension_affords_cost() {
+        let limiter = CompositeTokenBucketLimiter::new(options(4096, 5, 10));
+
+        let costs = HashMap::from([("bandwidth".to_string(), 1024), ("ops".to_string(), 1)]);
+        let lease = limiter.attempt_acquire_typed(&costs).unwrap();
+        assert!(lease.is_acquired());
+    }
+
+    #[test]
+    fn test_fails_without_debiting_any_dimension_and_reports_max_retry_after() {
+        let limiter = CompositeTokenBucketLimiter::new(options(4096, 2, 10));
+
+        // "ops" is short, so nothing should be debited, including "bandwidth".
+        let costs = HashMap::from([("bandwidth".to_string(), 10), ("ops".to_string(), 10)]);
+        let lease = limiter.attempt_acquire_typed(&costs).unwrap();
+        assert!(!lease.is_acquired());
+        assert_eq!(lease.retry_after(), Some(Duration::from_secs(60) * 4));
+
+        let bandwidth_only = HashMap::from([("bandwidth".to_string(), 4096)]);
+        let lease2 = limiter.attempt_acquire_typed(&bandwidth_only).unwrap();
+        assert!(lease2.is_acquired());
+    }
+
+    #[test]
+    fn test_unknown_bucket_name_is_an_error() {
+        let limiter = CompositeTokenBucketLimiter::new(options(10, 10, 10));
+        let costs = HashMap::from([("cpu".to_string(), 1)]);
+        assert!(limiter.attempt_acquire_typed(&costs).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_acquire_async_queues_until_every_dimension_frees_up() {
+        let mut opts = options(4096, 1, 10);
+        opts.buckets.insert(
+            "ops".to_string(),
+            BucketConfig::new(1, 1, Duration::from_millis(30)).unwrap(),
+        );
+        let limiter = Arc::new(CompositeTokenBucketLimiter::new(opts));
+        limiter.start_auto_replenishment();
+
+        let costs = HashMap::from([("bandwidth".to_string(), 1), ("ops".to_string(), 1)]);
+        let lease1 = limiter.attempt_acquire_typed(&costs).unwrap();
+        assert!(lease1.is_acquired());
+
+        let waiter = {
+            let limiter = Arc::clone(&limiter);
+            tokio::spawn(async move {
+                limiter
+                    .acquire_async_typed(
+                        HashMap::from([("bandwidth".to_string(), 1), ("ops".to_string(), 1)]),
+                        None,
+                    )
+                    .await
+            })
+        };
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!waiter.is_finished());
+
+        let lease2 = tokio::time::timeout(Duration::from_millis(200), waiter)
+            .await
+            .expect("queued request should resolve once the ops bucket replenishes")
+            .unwrap()
+            .unwrap();
+        assert!(lease2.is_acquired());
+    }
+}
@@ -81,6 +81,10 @@ struct QueuedRequest {
 
     /// Channel to send the result when permits become available.
     response: oneshot::Sender<Result<RateLimitLease, RateLimitError>>,
+
+    /// Time the request was pushed onto the queue, used to compute queue
+    /// wait-time statistics once the request is granted.
+    queued_at: Instant,
 }
 
 /// A rate limiter that manages concurrent access to a resource.
@@ -107,6 +111,16 @@ pub struct ConcurrencyLimiter {
 
     /// Counter for failed lease acquisitions.
     failed_leases: Arc<AtomicU64>,
+
+    /// Count of leases that had to wait in the queue before being granted.
+    queued_lease_count: Arc<AtomicU64>,
+
+    /// Running total of queue wait time (in nanoseconds) across leases
+    /// counted by `queued_lease_count`.
+    total_queue_wait_nanos: Arc<AtomicU64>,
+
+    /// Longest queue wait time (in nanoseconds) observed so far.
+    max_queue_wait_nanos: Arc<AtomicU64>,
 }
 
 impl ConcurrencyLimiter {
@@ -140,6 +154,9 @@ impl ConcurrencyLimiter {
             permit_return_rx: Arc::new(TokioMutex::new(permit_return_rx)),
             successful_leases: Arc::new(AtomicU64::new(0)),
             failed_leases: Arc::new(AtomicU64::new(0)),
+            queued_lease_count: Arc::new(AtomicU64::new(0)),
+            total_queue_wait_nanos: Arc::new(AtomicU64::new(0)),
+            max_queue_wait_nanos: Arc::new(AtomicU64::new(0)),
         })
     }
 
@@ -246,6 +263,7 @@ impl ConcurrencyLimiter {
 
                 state.available_permits -= req.permit_count;
                 state.queue_count -= req.permit_count;
+                self.record_queue_wait(req.queued_at.elapsed());
 
                 // Send lease with async cleanup (channel-based)
                 let lease = self.create_lease(req.permit_count, false);
@@ -287,6 +305,14 @@ impl ConcurrencyLimiter {
 
         None
     }
+
+    /// Record that a queued lease waited `wait` before being granted.
+    fn record_queue_wait(&self, wait: Duration) {
+        let nanos = wait.as_nanos().min(u128::from(u64::MAX)) as u64;
+        self.queued_lease_count.fetch_add(1, Ordering::Relaxed);
+        self.total_queue_wait_nanos.fetch_add(nanos, Ordering::Relaxed);
+        self.max_queue_wait_nanos.fetch_max(nanos, Ordering::Relaxed);
+    }
 }
 
 #[async_trait]
@@ -383,6 +409,7 @@ impl RateLimiter for ConcurrencyLimiter {
             let request = QueuedRequest {
                 permit_count,
                 response: tx,
+                queued_at: Instant::now(),
             };
 
             state.queue.push_back(request);
@@ -437,8 +464,17 @@ impl RateLimiter for ConcurrencyLimiter {
         RateLimiterStatistics {
             current_available_permits: state.available_permits as i64,
             current_queued_count: state.queue_count,
+            current_waiting_count: state.queue.len() as u32,
             total_successful_leases: self.successful_leases.load(Ordering::Relaxed),
             total_failed_leases: self.failed_leases.load(Ordering::Relaxed),
+            queued_lease_count: self.queued_lease_count.load(Ordering::Relaxed),
+            total_queue_wait_time: Duration::from_nanos(
+                self.total_queue_wait_nanos.load(Ordering::Relaxed),
+            ),
+            max_queue_wait_time: Duration::from_nanos(
+                self.max_queue_wait_nanos.load(Ordering::Relaxed),
+            ),
+            dropped_permits: 0,
         }
     }
 
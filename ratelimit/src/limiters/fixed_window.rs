@@ -3,9 +3,24 @@
 //! This module provides a rate limiter that uses the fixed window algorithm.
 //! Each window has a fixed duration and permit count. When the window expires,
 //! permits are reset to the full limit.
+//!
+//! Resetting all permits in one bulk step at window boundaries allows up to
+//! 2x the intended rate to pass across a boundary (a full window's permits
+//! just before the reset, plus a full window's permits just after). Callers
+//! that need smooth, fractional refill instead of that bursty reset should
+//! use `TokenBucketRateLimiter`, which already replenishes continuously
+//! (lazily catching up on elapsed time on every acquire, in addition to its
+//! optional timer) rather than in discrete window-sized jumps.
+//!
+//! `FixedWindowRateLimiter::reserve()` offers a non-queueing alternative to
+//! `attempt_acquire` that returns a `RateLimitReservation` instead of a
+//! `RateLimitLease`: permits are held immediately but returned to the
+//! window automatically if the reservation is dropped without calling
+//! `commit()`, which is useful for holding capacity across a fallible step.
 
 use crate::core::traits::{RateLimiter, ReplenishingRateLimiter};
 use crate::core::{QueueProcessingOrder, RateLimitError, RateLimitLease, RateLimiterStatistics};
+use crate::utils::{Clock, SystemClock};
 use async_trait::async_trait;
 use parking_lot::Mutex;
 use std::collections::VecDeque;
@@ -16,7 +31,7 @@ use tokio::sync::oneshot;
 use tokio_util::sync::CancellationToken;
 
 /// Options for configuring a `FixedWindowRateLimiter`.
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct FixedWindowRateLimiterOptions {
     /// Maximum number of permits available per window.
     pub permit_limit: u32,
@@ -33,6 +48,28 @@ pub struct FixedWindowRateLimiterOptions {
     /// If true, windows automatically advance on a timer.
     /// If false, `try_replenish()` must be called manually to advance windows.
     pub auto_replenishment: bool,
+
+    /// Clock used to timestamp window starts and expire windows.
+    ///
+    /// Defaults to [`SystemClock`]. `new()` cannot take this as a positional
+    /// parameter without breaking every existing call site, so override it
+    /// afterwards with struct-update syntax, e.g. swapping in a
+    /// [`ManualClock`](crate::utils::ManualClock) for deterministic tests:
+    /// `FixedWindowRateLimiterOptions { clock: Arc::new(clock), ..options }`.
+    pub clock: Arc<dyn Clock>,
+}
+
+impl std::fmt::Debug for FixedWindowRateLimiterOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FixedWindowRateLimiterOptions")
+            .field("permit_limit", &self.permit_limit)
+            .field("window", &self.window)
+            .field("queue_limit", &self.queue_limit)
+            .field("queue_processing_order", &self.queue_processing_order)
+            .field("auto_replenishment", &self.auto_replenishment)
+            .field("clock", &"<dyn Clock>")
+            .finish()
+    }
 }
 
 impl FixedWindowRateLimiterOptions {
@@ -68,6 +105,7 @@ impl FixedWindowRateLimiterOptions {
             queue_limit,
             queue_processing_order,
             auto_replenishment,
+            clock: Arc::new(SystemClock),
         })
     }
 }
@@ -100,6 +138,10 @@ struct QueuedRequest {
 
     /// Channel to send the result when permits become available.
     response: oneshot::Sender<Result<RateLimitLease, RateLimitError>>,
+
+    /// Time the request was pushed onto the queue, used to compute queue
+    /// wait-time statistics once the request is granted.
+    queued_at: Instant,
 }
 
 /// A rate limiter that uses the fixed window algorithm.
@@ -120,6 +162,16 @@ pub struct FixedWindowRateLimiter {
     /// Counter for failed lease acquisitions.
     failed_leases: Arc<AtomicU64>,
 
+    /// Count of leases that had to wait in the queue before being granted.
+    queued_lease_count: Arc<AtomicU64>,
+
+    /// Running total of queue wait time (in nanoseconds) across leases
+    /// counted by `queued_lease_count`.
+    total_queue_wait_nanos: Arc<AtomicU64>,
+
+    /// Longest queue wait time (in nanoseconds) observed so far.
+    max_queue_wait_nanos: Arc<AtomicU64>,
+
     /// Cancellation token for stopping the replenishment timer.
     replenishment_cancel: CancellationToken,
 }
@@ -144,7 +196,7 @@ impl FixedWindowRateLimiter {
             ));
         }
 
-        let now = Instant::now();
+        let now = options.clock.now();
         let state = State {
             available_permits: options.permit_limit,
             window_start: now,
@@ -159,6 +211,9 @@ impl FixedWindowRateLimiter {
             config: options,
             successful_leases: Arc::new(AtomicU64::new(0)),
             failed_leases: Arc::new(AtomicU64::new(0)),
+            queued_lease_count: Arc::new(AtomicU64::new(0)),
+            total_queue_wait_nanos: Arc::new(AtomicU64::new(0)),
+            max_queue_wait_nanos: Arc::new(AtomicU64::new(0)),
             replenishment_cancel: CancellationToken::new(),
         })
     }
@@ -193,7 +248,7 @@ impl FixedWindowRateLimiter {
 
         // Reset to full permits for the new window
         state.available_permits = self.config.permit_limit;
-        state.window_start = Instant::now();
+        state.window_start = self.config.clock.now();
 
         // Update idle tracking
         if state.available_permits == self.config.permit_limit && state.idle_since.is_none() {
@@ -207,11 +262,11 @@ impl FixedWindowRateLimiter {
     /// Check if the current window has expired (for manual mode).
     fn check_window_expiration(&self, state: &mut State) {
         if !self.config.auto_replenishment {
-            let elapsed = Instant::now().duration_since(state.window_start);
+            let elapsed = self.config.clock.now().duration_since(state.window_start);
             if elapsed >= self.config.window {
                 // Window expired, reset
                 state.available_permits = self.config.permit_limit;
-                state.window_start = Instant::now();
+                state.window_start = self.config.clock.now();
 
                 // Update idle tracking
                 if state.available_permits == self.config.permit_limit && state.idle_since.is_none() {
@@ -222,10 +277,42 @@ impl FixedWindowRateLimiter {
     }
 
     /// Process queued requests (single location for all queue logic).
+    ///
+    /// A thin wrapper around `process_queue_static` that supplies this
+    /// limiter's own fields - see that function for the shared logic, which
+    /// also runs from a rolled-back `RateLimitReservation`'s drop handler
+    /// where no `&self` is available.
     fn process_queue_internal(&self, state: &mut State) {
+        Self::process_queue_static(
+            state,
+            self.config.queue_processing_order,
+            &self.config.clock,
+            &self.successful_leases,
+            &self.queued_lease_count,
+            &self.total_queue_wait_nanos,
+            &self.max_queue_wait_nanos,
+        );
+    }
+
+    /// Shared queue-draining logic, taking its dependencies as plain
+    /// references/values instead of `&self` so it can run from contexts
+    /// that only hold a cloned `Arc<Mutex<State>>` plus a handful of
+    /// `Arc<AtomicU64>` counters - namely a `RateLimitReservation`'s rollback
+    /// closure, which must be `'static` and outlive the limiter it came
+    /// from.
+    #[allow(clippy::too_many_arguments)]
+    fn process_queue_static(
+        state: &mut State,
+        queue_processing_order: QueueProcessingOrder,
+        clock: &Arc<dyn Clock>,
+        successful_leases: &AtomicU64,
+        queued_lease_count: &AtomicU64,
+        total_queue_wait_nanos: &AtomicU64,
+        max_queue_wait_nanos: &AtomicU64,
+    ) {
         while !state.queue.is_empty() {
             // Check next request based on processing order
-            let next = match self.config.queue_processing_order {
+            let next = match queue_processing_order {
                 QueueProcessingOrder::OldestFirst => state.queue.front(),
                 QueueProcessingOrder::NewestFirst => state.queue.back(),
             };
@@ -236,7 +323,7 @@ impl FixedWindowRateLimiter {
 
             // Skip closed/cancelled requests
             if next_req.response.is_closed() {
-                let req = match self.config.queue_processing_order {
+                let req = match queue_processing_order {
                     QueueProcessingOrder::OldestFirst => state.queue.pop_front(),
                     QueueProcessingOrder::NewestFirst => state.queue.pop_back(),
                 };
@@ -249,7 +336,7 @@ impl FixedWindowRateLimiter {
 
             // Check if we have enough permits
             if state.available_permits >= next_req.permit_count {
-                let req = match self.config.queue_processing_order {
+                let req = match queue_processing_order {
                     QueueProcessingOrder::OldestFirst => state.queue.pop_front(),
                     QueueProcessingOrder::NewestFirst => state.queue.pop_back(),
                 }.unwrap();
@@ -257,11 +344,17 @@ impl FixedWindowRateLimiter {
                 state.available_permits -= req.permit_count;
                 state.queue_count -= req.permit_count;
                 state.idle_since = None;
+                Self::record_queue_wait_static(
+                    queued_lease_count,
+                    total_queue_wait_nanos,
+                    max_queue_wait_nanos,
+                    clock.now().duration_since(req.queued_at),
+                );
 
                 // Send lease (no cleanup needed - permits don't return)
-                let lease = self.create_lease(req.permit_count);
+                let lease = Self::create_lease(req.permit_count);
                 let _ = req.response.send(Ok(lease));
-                self.successful_leases.fetch_add(1, Ordering::Relaxed);
+                successful_leases.fetch_add(1, Ordering::Relaxed);
             } else {
                 // Not enough permits for next request
                 break;
@@ -270,7 +363,7 @@ impl FixedWindowRateLimiter {
     }
 
     /// Create a lease (no cleanup needed for fixed window).
-    fn create_lease(&self, permit_count: u32) -> RateLimitLease {
+    fn create_lease(permit_count: u32) -> RateLimitLease {
         if permit_count == 0 {
             RateLimitLease::success()
         } else {
@@ -282,7 +375,7 @@ impl FixedWindowRateLimiter {
     /// Calculate retry-after duration for failed requests.
     fn calculate_retry_after(&self, state: &State, permit_count: u32) -> Duration {
         // Calculate time remaining in current window
-        let elapsed = Instant::now().duration_since(state.window_start);
+        let elapsed = self.config.clock.now().duration_since(state.window_start);
         let remaining_in_window = self.config.window.saturating_sub(elapsed);
 
         // If we have enough permits in this window, retry immediately
@@ -326,12 +419,162 @@ impl FixedWindowRateLimiter {
                 self.successful_leases.fetch_add(1, Ordering::Relaxed);
 
                 // Create lease (no cleanup needed)
-                return Some(self.create_lease(permit_count));
+                return Some(Self::create_lease(permit_count));
             }
         }
 
         None
     }
+
+    /// Shared implementation of queue-wait accounting, taking the counters as
+    /// plain references - see `process_queue_static` for why.
+    fn record_queue_wait_static(
+        queued_lease_count: &AtomicU64,
+        total_queue_wait_nanos: &AtomicU64,
+        max_queue_wait_nanos: &AtomicU64,
+        wait: Duration,
+    ) {
+        let nanos = wait.as_nanos().min(u128::from(u64::MAX)) as u64;
+        queued_lease_count.fetch_add(1, Ordering::Relaxed);
+        total_queue_wait_nanos.fetch_add(nanos, Ordering::Relaxed);
+        max_queue_wait_nanos.fetch_max(nanos, Ordering::Relaxed);
+    }
+
+    /// Reserve `permit_count` permits from the current window immediately,
+    /// without queueing.
+    ///
+    /// Unlike `attempt_acquire`, the returned handle is not a final
+    /// [`RateLimitLease`] - it is a [`RateLimitReservation`] that gives the
+    /// permits back to the window (and wakes any queued waiters) if it is
+    /// dropped before `commit()` is called. This mirrors the
+    /// reserve/commit-or-rollback pattern of `tokio::sync::mpsc::Sender::reserve`,
+    /// and is useful when a caller needs to hold capacity across a fallible
+    /// operation (e.g. building a request) without permanently consuming
+    /// permits if that operation fails or is cancelled.
+    ///
+    /// Returns `Ok(None)` if `permit_count` permits are not available right
+    /// now (this method never queues). Returns
+    /// `Err(RateLimitError::PermitCountExceeded)` if `permit_count` is
+    /// larger than `permit_limit` and so could never succeed, and
+    /// `Err(RateLimitError::Disposed)` if the limiter has been disposed.
+    pub fn reserve(
+        &self,
+        permit_count: u32,
+    ) -> Result<Option<RateLimitReservation>, RateLimitError> {
+        if permit_count > self.config.permit_limit {
+            return Err(RateLimitError::PermitCountExceeded(
+                permit_count,
+                self.config.permit_limit,
+            ));
+        }
+
+        let mut state = self.state.lock();
+        if state.disposed {
+            return Err(RateLimitError::Disposed);
+        }
+
+        self.check_window_expiration(&mut state);
+
+        if permit_count == 0 {
+            return Ok(Some(RateLimitReservation::no_op()));
+        }
+
+        if state.available_permits < permit_count {
+            return Ok(None);
+        }
+
+        state.available_permits -= permit_count;
+        state.idle_since = None;
+
+        let state_handle = Arc::clone(&self.state);
+        let permit_limit = self.config.permit_limit;
+        let queue_processing_order = self.config.queue_processing_order;
+        let clock = Arc::clone(&self.config.clock);
+        let successful_leases = Arc::clone(&self.successful_leases);
+        let queued_lease_count = Arc::clone(&self.queued_lease_count);
+        let total_queue_wait_nanos = Arc::clone(&self.total_queue_wait_nanos);
+        let max_queue_wait_nanos = Arc::clone(&self.max_queue_wait_nanos);
+
+        Ok(Some(RateLimitReservation::new(permit_count, move |returned| {
+            let mut state = state_handle.lock();
+            if state.disposed {
+                return;
+            }
+
+            state.available_permits = (state.available_permits + returned).min(permit_limit);
+            if state.available_permits == permit_limit && state.idle_since.is_none() {
+                state.idle_since = Some(clock.now());
+            }
+
+            Self::process_queue_static(
+                &mut state,
+                queue_processing_order,
+                &clock,
+                &successful_leases,
+                &queued_lease_count,
+                &total_queue_wait_nanos,
+                &max_queue_wait_nanos,
+            );
+        })))
+    }
+}
+
+/// An RAII handle returned by [`FixedWindowRateLimiter::reserve`].
+///
+/// The reserved permits are already subtracted from the window's available
+/// count when this is returned. Call [`commit`](RateLimitReservation::commit)
+/// to finalize the reservation into a granted [`RateLimitLease`], or simply
+/// drop the reservation to return the permits to the window (waking any
+/// queued waiters in the process).
+#[must_use = "dropping a reservation without calling commit() returns its permits to the window"]
+pub struct RateLimitReservation {
+    permit_count: u32,
+    rollback: Option<Box<dyn FnOnce(u32) + Send>>,
+}
+
+impl RateLimitReservation {
+    fn new(permit_count: u32, rollback: impl FnOnce(u32) + Send + 'static) -> Self {
+        Self {
+            permit_count,
+            rollback: Some(Box::new(rollback)),
+        }
+    }
+
+    /// A reservation of zero permits, which has nothing to roll back.
+    fn no_op() -> Self {
+        Self {
+            permit_count: 0,
+            rollback: None,
+        }
+    }
+
+    /// The number of permits held by this reservation.
+    pub fn permit_count(&self) -> u32 {
+        self.permit_count
+    }
+
+    /// Finalize the reservation: the held permits are kept, and a granted
+    /// [`RateLimitLease`] is returned in their place.
+    pub fn commit(mut self) -> RateLimitLease {
+        self.rollback = None;
+        RateLimitLease::success()
+    }
+}
+
+impl std::fmt::Debug for RateLimitReservation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimitReservation")
+            .field("permit_count", &self.permit_count)
+            .finish()
+    }
+}
+
+impl Drop for RateLimitReservation {
+    fn drop(&mut self) {
+        if let Some(rollback) = self.rollback.take() {
+            rollback(self.permit_count);
+        }
+    }
 }
 
 #[async_trait]
@@ -435,6 +678,7 @@ impl RateLimiter for FixedWindowRateLimiter {
             let request = QueuedRequest {
                 permit_count,
                 response: tx,
+                queued_at: self.config.clock.now(),
             };
 
             state.queue.push_back(request);
@@ -489,14 +733,24 @@ impl RateLimiter for FixedWindowRateLimiter {
         RateLimiterStatistics {
             current_available_permits: state.available_permits as i64,
             current_queued_count: state.queue_count,
+            current_waiting_count: state.queue.len() as u32,
             total_successful_leases: self.successful_leases.load(Ordering::Relaxed),
             total_failed_leases: self.failed_leases.load(Ordering::Relaxed),
+            queued_lease_count: self.queued_lease_count.load(Ordering::Relaxed),
+            total_queue_wait_time: Duration::from_nanos(
+                self.total_queue_wait_nanos.load(Ordering::Relaxed),
+            ),
+            max_queue_wait_time: Duration::from_nanos(
+                self.max_queue_wait_nanos.load(Ordering::Relaxed),
+            ),
+            dropped_permits: 0,
         }
     }
 
     fn idle_duration(&self) -> Option<Duration> {
         let state = self.state.lock();
-        state.idle_since.map(|since| since.elapsed())
+        let now = self.config.clock.now();
+        state.idle_since.map(|since| now.duration_since(since))
     }
 }
 
@@ -541,6 +795,7 @@ impl Drop for FixedWindowRateLimiter {
 mod tests {
     use super::*;
     use crate::core::QueueProcessingOrder;
+    use crate::utils::ManualClock;
     use std::sync::Arc;
     use std::time::Duration;
     use tokio::time::sleep;
@@ -668,4 +923,143 @@ mod tests {
         let lease3 = limiter.attempt_acquire(10).unwrap();
         assert!(lease3.is_acquired());
     }
+
+    #[test]
+    fn test_reserve_and_commit() {
+        let options = FixedWindowRateLimiterOptions::new(
+            10,  // permit_limit
+            Duration::from_millis(100),  // window
+            20,  // queue_limit
+            QueueProcessingOrder::OldestFirst,
+            false,  // auto_replenishment
+        )
+        .unwrap();
+
+        let limiter = FixedWindowRateLimiter::new(options).unwrap();
+
+        let reservation = limiter.reserve(6).unwrap().unwrap();
+        assert_eq!(reservation.permit_count(), 6);
+
+        let stats = limiter.get_statistics();
+        assert_eq!(stats.current_available_permits, 4);
+
+        let lease = reservation.commit();
+        assert!(lease.is_acquired());
+
+        // Committing keeps the permits consumed.
+        let stats = limiter.get_statistics();
+        assert_eq!(stats.current_available_permits, 4);
+    }
+
+    #[test]
+    fn test_reserve_returns_none_when_insufficient_permits() {
+        let options = FixedWindowRateLimiterOptions::new(
+            10,  // permit_limit
+            Duration::from_millis(100),  // window
+            20,  // queue_limit
+            QueueProcessingOrder::OldestFirst,
+            false,  // auto_replenishment
+        )
+        .unwrap();
+
+        let limiter = FixedWindowRateLimiter::new(options).unwrap();
+
+        assert!(limiter.reserve(11).is_err());
+        let held = limiter.reserve(10).unwrap();
+        assert!(held.is_some());
+        // All 10 permits are now held by the reservation above.
+        assert!(limiter.reserve(1).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_dropped_reservation_returns_permits() {
+        let options = FixedWindowRateLimiterOptions::new(
+            10,  // permit_limit
+            Duration::from_millis(100),  // window
+            20,  // queue_limit
+            QueueProcessingOrder::OldestFirst,
+            false,  // auto_replenishment
+        )
+        .unwrap();
+
+        let limiter = FixedWindowRateLimiter::new(options).unwrap();
+
+        {
+            let reservation = limiter.reserve(7).unwrap().unwrap();
+            assert_eq!(reservation.permit_count(), 7);
+            let stats = limiter.get_statistics();
+            assert_eq!(stats.current_available_permits, 3);
+            // Dropped without calling commit() - permits should return.
+        }
+
+        let stats = limiter.get_statistics();
+        assert_eq!(stats.current_available_permits, 10);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_reservation_wakes_queued_waiter() {
+        let options = FixedWindowRateLimiterOptions::new(
+            10,  // permit_limit
+            Duration::from_millis(500),  // window
+            20,  // queue_limit
+            QueueProcessingOrder::OldestFirst,
+            false,  // auto_replenishment
+        )
+        .unwrap();
+
+        let limiter = Arc::new(FixedWindowRateLimiter::new(options).unwrap());
+
+        // Hold all but 2 permits via a reservation.
+        let reservation = limiter.reserve(8).unwrap().unwrap();
+
+        // Queue a waiter that needs more than the 2 remaining permits.
+        let limiter_clone = Arc::clone(&limiter);
+        let waiter = tokio::spawn(async move { limiter_clone.acquire_async(5, None).await });
+
+        // Give the waiter time to enqueue.
+        sleep(Duration::from_millis(20)).await;
+
+        // Rolling back the reservation returns 8 permits, which should wake
+        // the queued waiter.
+        drop(reservation);
+
+        let lease = waiter.await.unwrap().unwrap();
+        assert!(lease.is_acquired());
+    }
+
+    #[test]
+    fn test_window_expiration_with_manual_clock() {
+        // Same scenario as `test_window_expiration_in_manual_mode`, but
+        // driven by a `ManualClock` instead of a real sleep - the window
+        // boundary is crossed deterministically and instantly.
+        let clock = ManualClock::new();
+        let options = FixedWindowRateLimiterOptions {
+            clock: Arc::new(clock.clone()),
+            ..FixedWindowRateLimiterOptions::new(
+                10,  // permit_limit
+                Duration::from_millis(100),  // window
+                20,  // queue_limit
+                QueueProcessingOrder::OldestFirst,
+                false,  // auto_replenishment - MANUAL mode
+            )
+            .unwrap()
+        };
+
+        let limiter = FixedWindowRateLimiter::new(options).unwrap();
+
+        // Use all permits
+        let lease1 = limiter.attempt_acquire(10).unwrap();
+        assert!(lease1.is_acquired());
+
+        // Should fail - no permits
+        let lease2 = limiter.attempt_acquire(1).unwrap();
+        assert!(!lease2.is_acquired());
+
+        // Advance past the window boundary without any real waiting.
+        clock.advance(Duration::from_millis(110));
+
+        // Should succeed - window auto-expired on attempt
+        let lease3 = limiter.attempt_acquire(10).unwrap();
+        assert!(lease3.is_acquired());
+    }
 }
\ No newline at end of file
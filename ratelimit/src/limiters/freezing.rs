@@ -0,0 +1,220 @@
+//! Freeze-and-retry wrapper limiter.
+//!
+//! Wraps an inner rate limiter and adds cooperative backpressure on top of it:
+//! when a caller reports a `Retry-After` hint from a downstream dependency, the
+//! whole wrapper "freezes" for that duration, regardless of what the inner
+//! limiter itself would otherwise allow.
+
+use crate::core::{RateLimitError, RateLimitLease, RateLimiter, RateLimiterStatistics};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+/// A rate limiter decorator that honors `Retry-After` hints from a downstream
+/// dependency by freezing all acquisitions for the reported duration.
+///
+/// # Example
+///
+/// ```no_run
+/// use ratelimit::limiters::{FreezingLimiter, TokenBucketRateLimiter, TokenBucketRateLimiterOptions};
+/// use ratelimit::core::{RateLimiter, QueueProcessingOrder};
+/// use ratelimit::utils::SystemClock;
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let inner = TokenBucketRateLimiter::new(TokenBucketRateLimiterOptions {
+///     tokens_per_period: 10,
+///     token_limit: 10,
+///     replenishment_period: Duration::from_secs(1),
+///     auto_replenishment: true,
+///     queue_limit: 0,
+///     queue_processing_order: QueueProcessingOrder::OldestFirst,
+///     one_time_burst: 0,
+///     replenish_fractionally: false,
+///     clock: Arc::new(SystemClock),
+///     ready_threshold: 1,
+/// }).unwrap();
+/// let limiter = FreezingLimiter::new(inner);
+///
+/// // A downstream 429 told us to back off for 30 seconds.
+/// limiter.report_retry_after(Duration::from_secs(30));
+///
+/// // Non-blocking callers fail immediately with the remaining freeze time.
+/// let lease = limiter.attempt_acquire(1).unwrap();
+/// assert!(!lease.is_acquired());
+/// ```
+pub struct FreezingLimiter<L: RateLimiter> {
+    inner: L,
+    freeze_until: Mutex<Option<Instant>>,
+    notify: Notify,
+}
+
+impl<L: RateLimiter> FreezingLimiter<L> {
+    /// Wrap `inner` in a limiter that can be frozen via `report_retry_after`.
+    pub fn new(inner: L) -> Self {
+        Self {
+            inner,
+            freeze_until: Mutex::new(None),
+            notify: Notify::new(),
+        }
+    }
+
+    /// Access the wrapped limiter.
+    pub fn inner(&self) -> &L {
+        &self.inner
+    }
+
+    /// Report a `Retry-After` hint observed from a downstream response.
+    ///
+    /// Extends the freeze window to `now + retry_after` if that is later than
+    /// any freeze already in effect; never shortens an existing freeze.
+    /// Waiters blocked in `acquire_async` are woken so they can re-check the
+    /// (possibly extended) deadline.
+    pub fn report_retry_after(&self, retry_after: Duration) {
+        let until = Instant::now() + retry_after;
+        {
+            let mut freeze_until = self.freeze_until.lock();
+            let should_update = match *freeze_until {
+                Some(existing) => until > existing,
+                None => true,
+            };
+            if should_update {
+                *freeze_until = Some(until);
+            }
+        }
+        self.notify.notify_waiters();
+    }
+
+    /// Returns `true` if the limiter is currently frozen.
+    pub fn is_frozen(&self) -> bool {
+        self.remaining_freeze().is_some()
+    }
+
+    /// Remaining freeze duration, or `None` if not frozen (or the freeze has
+    /// already elapsed).
+    fn remaining_freeze(&self) -> Option<Duration> {
+        let freeze_until = *self.freeze_until.lock();
+        freeze_until.and_then(|until| until.checked_duration_since(Instant::now()))
+    }
+}
+
+#[async_trait]
+impl<L: RateLimiter> RateLimiter for FreezingLimiter<L> {
+    fn attempt_acquire(&self, permit_count: u32) -> Result<RateLimitLease, RateLimitError> {
+        if let Some(remaining) = self.remaining_freeze() {
+            return Ok(RateLimitLease::failed(Some(remaining)));
+        }
+        self.inner.attempt_acquire(permit_count)
+    }
+
+    async fn acquire_async(
+        &self,
+        permit_count: u32,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        let cancel = cancel_token.clone().unwrap_or_default();
+
+        loop {
+            let Some(remaining) = self.remaining_freeze() else {
+                break;
+            };
+
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            tokio::select! {
+                _ = tokio::time::sleep(remaining) => {}
+                _ = &mut notified => {}
+                _ = cancel.cancelled() => return Err(RateLimitError::Cancelled),
+            }
+        }
+
+        // The freeze has elapsed: retry once against the inner limiter,
+        // preserving whatever FIFO ordering it provides for queued requests.
+        self.inner.acquire_async(permit_count, cancel_token).await
+    }
+
+    fn get_statistics(&self) -> RateLimiterStatistics {
+        self.inner.get_statistics()
+    }
+
+    fn idle_duration(&self) -> Option<Duration> {
+        self.inner.idle_duration()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::limiters::{ConcurrencyLimiter, ConcurrencyLimiterOptions};
+    use crate::QueueProcessingOrder;
+
+    fn unlimited_inner() -> ConcurrencyLimiter {
+        ConcurrencyLimiter::new(ConcurrencyLimiterOptions {
+            permit_limit: 10,
+            queue_limit: 10,
+            queue_processing_order: QueueProcessingOrder::OldestFirst,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_not_frozen_delegates_to_inner() {
+        let limiter = FreezingLimiter::new(unlimited_inner());
+        assert!(!limiter.is_frozen());
+
+        let lease = limiter.attempt_acquire(1).unwrap();
+        assert!(lease.is_acquired());
+    }
+
+    #[test]
+    fn test_attempt_acquire_fails_immediately_while_frozen() {
+        let limiter = FreezingLimiter::new(unlimited_inner());
+        limiter.report_retry_after(Duration::from_secs(60));
+
+        let lease = limiter.attempt_acquire(1).unwrap();
+        assert!(!lease.is_acquired());
+        assert!(lease.retry_after().unwrap() <= Duration::from_secs(60));
+        assert!(lease.retry_after().unwrap() > Duration::from_secs(0));
+    }
+
+    #[test]
+    fn test_report_retry_after_only_extends_the_freeze() {
+        let limiter = FreezingLimiter::new(unlimited_inner());
+        limiter.report_retry_after(Duration::from_secs(60));
+        limiter.report_retry_after(Duration::from_millis(1));
+
+        let lease = limiter.attempt_acquire(1).unwrap();
+        assert!(lease.retry_after().unwrap() > Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_async_waits_out_the_freeze_then_retries_inner() {
+        let limiter = std::sync::Arc::new(FreezingLimiter::new(unlimited_inner()));
+        limiter.report_retry_after(Duration::from_millis(30));
+
+        let result = tokio::time::timeout(Duration::from_millis(200), limiter.acquire_async(1, None))
+            .await
+            .expect("acquire_async should resolve once the freeze elapses")
+            .unwrap();
+        assert!(result.is_acquired());
+        assert!(!limiter.is_frozen());
+    }
+
+    #[tokio::test]
+    async fn test_acquire_async_cancels_while_frozen() {
+        let limiter = FreezingLimiter::new(unlimited_inner());
+        limiter.report_retry_after(Duration::from_secs(60));
+
+        let cancel = CancellationToken::new();
+        let cancel_clone = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            cancel_clone.cancel();
+        });
+
+        let result = limiter.acquire_async(1, Some(cancel)).await;
+        assert!(matches!(result, Err(RateLimitError::Cancelled)));
+    }
+}
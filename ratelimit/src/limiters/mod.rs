@@ -5,19 +5,46 @@
 //! - `FixedWindowRateLimiter` - Fixed time window limiting
 //! - `SlidingWindowRateLimiter` - Sliding window limiting
 //! - `ConcurrencyLimiter` - Concurrent request limiting
-//! - `ChainedRateLimiter` - Combines multiple limiters
+//! - `AimdConcurrencyLimiter` - Self-tuning concurrency limiting via AIMD feedback
+//! - `CiadLimiter` - Cautious-increase/aggressive-decrease concurrency limiting
+//! - `ChainedRateLimiter` - Combines multiple limiters via a `ChainPolicy` (`All` or `Any`)
+//! - `MultiTokenBucketLimiter` - Independent `Bytes`/`Ops` budgets enforced together
+//! - `CompositeTokenBucketLimiter` - Arbitrary named budgets enforced together, with queueing
+//! - `FreezingLimiter` - Wraps a limiter and freezes it on a `Retry-After` hint
+//! - `ResponsiveTokenBucketLimiter` - Wraps a token bucket and adapts its rate via AIMD feedback
+//! - `RateLimitReservation` - RAII handle from `FixedWindowRateLimiter::reserve`, rolls back on drop unless committed
+//! - `LeakyBucketRateLimiter` - Steady-rate leak with precisely scheduled wakeups, instead of batch replenishment
+//! - `ServerFeedbackLimiter` - Reconciles a wrapped limiter against a downstream API's own reported rate limit state
 
 // Module declarations
+pub mod aimd;
 pub mod chained;
+pub mod ciad;
+pub mod composite_token_bucket;
 pub mod concurrency;
 pub mod fixed_window;
+pub mod freezing;
+pub mod leaky_bucket;
+pub mod multi_bucket;
+pub mod multi_token_bucket;
+pub mod responsive;
+pub mod server_feedback;
 /// Sliding window rate limiter implementation
 pub mod sliding_window;
 pub mod token_bucket;
 
 // Re-exports
-pub use chained::ChainedRateLimiter;
+pub use aimd::{AimdConcurrencyLimiter, AimdConcurrencyLimiterOptions};
+pub use chained::{ChainPolicy, ChainedRateLimiter};
+pub use ciad::{CiadBehavior, CiadClassification, CiadLimiter, CiadLimiterOptions};
+pub use composite_token_bucket::{CompositeTokenBucketLimiter, CompositeTokenBucketLimiterOptions};
 pub use concurrency::{ConcurrencyLimiter, ConcurrencyLimiterOptions};
-pub use fixed_window::{FixedWindowRateLimiter, FixedWindowRateLimiterOptions};
+pub use freezing::FreezingLimiter;
+pub use fixed_window::{FixedWindowRateLimiter, FixedWindowRateLimiterOptions, RateLimitReservation};
+pub use leaky_bucket::{LeakyBucketRateLimiter, LeakyBucketRateLimiterOptions};
+pub use multi_bucket::{BucketConfig, MultiBucketRateLimiter};
+pub use multi_token_bucket::{MultiTokenBucketLimiter, MultiTokenBucketLimiterOptions, TokenType};
+pub use responsive::{ResponsiveTokenBucketLimiter, ResponsiveTokenBucketLimiterOptions};
+pub use server_feedback::{RateLimitHeaders, ServerFeedbackLimiter};
 pub use sliding_window::{SlidingWindowRateLimiter, SlidingWindowRateLimiterOptions};
-pub use token_bucket::{TokenBucketRateLimiter, TokenBucketRateLimiterOptions};
+pub use token_bucket::{BucketUpdate, TokenBucketRateLimiter, TokenBucketRateLimiterOptions};
@@ -0,0 +1,546 @@
+//! Multi-dimensional (bytes + operations) token-bucket limiter.
+//!
+//! Complements `MultiBucketRateLimiter`'s dynamically-named buckets with a
+//! fixed two-dimensional `TokenType::{Bytes, Ops}` budget modeled on
+//! block-device QoS: bandwidth and operation-rate ceilings enforced by one
+//! limiter instead of stacking two independent `TokenBucketRateLimiter`s
+//! (which could debit one budget and then block forever on the other).
+//! Unlike `MultiBucketRateLimiter::attempt_acquire_typed`, which fails
+//! immediately if any bucket is short, `acquire_async_typed` queues the
+//! request until both budgets can be satisfied together. A lease failed by
+//! `attempt_acquire_typed` carries both `"BlockedTokenType"` metadata naming a dimension that came
+//! up short and a `RetryAfter` duration - the longer of the two dimensions'
+//! individual wait times.
+//!
+//! `attempt_acquire_cost`/`acquire_async_cost` take a `&[(TokenType, u32)]`
+//! cost list instead of a `HashMap`, for callers who'd rather write out a
+//! short list of dimensions than build a map for two entries.
+
+use crate::core::traits::RateLimiter;
+use crate::core::{QueueProcessingOrder, RateLimitError, RateLimitLease, RateLimiterStatistics};
+use crate::limiters::multi_bucket::BucketConfig;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+
+/// Which budget a `MultiTokenBucketLimiter` cost applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenType {
+    /// Bandwidth budget, typically bytes/sec.
+    Bytes,
+    /// Operation-rate budget, typically ops/sec.
+    Ops,
+}
+
+/// Options for configuring a `MultiTokenBucketLimiter`.
+#[derive(Clone, Debug)]
+pub struct MultiTokenBucketLimiterOptions {
+    /// Bucket configuration for the `Bytes` dimension.
+    pub bytes: BucketConfig,
+
+    /// Bucket configuration for the `Ops` dimension.
+    pub ops: BucketConfig,
+
+    /// Maximum total cost (summed across both dimensions) that can be queued.
+    pub queue_limit: u32,
+
+    /// Order in which queued requests are processed.
+    pub queue_processing_order: QueueProcessingOrder,
+}
+
+/// Runtime state for a single dimension's bucket.
+struct BucketState {
+    available: f64,
+    last_replenished: Instant,
+}
+
+/// A queued request waiting for both dimensions to have capacity.
+struct QueuedRequest {
+    costs: HashMap<TokenType, u32>,
+    response: oneshot::Sender<Result<RateLimitLease, RateLimitError>>,
+    queued_at: Instant,
+}
+
+/// Internal state shared across both dimensions.
+struct State {
+    bytes: BucketState,
+    ops: BucketState,
+    queue: VecDeque<QueuedRequest>,
+    queue_count: u32,
+    idle_since: Option<Instant>,
+    disposed: bool,
+}
+
+/// A rate limiter enforcing independent `Bytes` and `Ops` budgets, granting
+/// a lease only when both dimensions can afford the request's cost.
+///
+/// `attempt_acquire_typed` checks both buckets immediately and fails
+/// without debiting either if one is short, tagging the failed lease with
+/// the `"BlockedTokenType"` metadata naming the first dimension that
+/// couldn't afford its cost and a `RetryAfter` duration covering the longer
+/// of the two dimensions' waits. `acquire_async_typed` instead queues the
+/// request until both buckets can be satisfied together. The plain
+/// `RateLimiter` trait methods apply `permit_count` uniformly to both
+/// dimensions, for callers that don't need independently sized costs.
+pub struct MultiTokenBucketLimiter {
+    config_bytes: BucketConfig,
+    config_ops: BucketConfig,
+    queue_limit: u32,
+    queue_processing_order: QueueProcessingOrder,
+    state: Arc<Mutex<State>>,
+    successful_leases: Arc<AtomicU64>,
+    failed_leases: Arc<AtomicU64>,
+    replenish_cancel: CancellationToken,
+}
+
+impl MultiTokenBucketLimiter {
+    /// Create a new multi-token-bucket limiter with the specified options.
+    pub fn new(options: MultiTokenBucketLimiterOptions) -> Self {
+        let now = Instant::now();
+
+        Self {
+            config_bytes: options.bytes.clone(),
+            config_ops: options.ops.clone(),
+            queue_limit: options.queue_limit,
+            queue_processing_order: options.queue_processing_order,
+            state: Arc::new(Mutex::new(State {
+                bytes: BucketState {
+                    available: options.bytes.token_limit as f64,
+                    last_replenished: now,
+                },
+                ops: BucketState {
+                    available: options.ops.token_limit as f64,
+                    last_replenished: now,
+                },
+                queue: VecDeque::new(),
+                queue_count: 0,
+                idle_since: Some(now),
+                disposed: false,
+            })),
+            successful_leases: Arc::new(AtomicU64::new(0)),
+            failed_leases: Arc::new(AtomicU64::new(0)),
+            replenish_cancel: CancellationToken::new(),
+        }
+    }
+
+    /// Spawns background tasks that replenish the `Bytes` and `Ops` buckets
+    /// on their own independent periods until this limiter is dropped.
+    pub fn start_auto_replenishment(self: &Arc<Self>) {
+        for token_type in [TokenType::Bytes, TokenType::Ops] {
+            let limiter = Arc::downgrade(self);
+            let period = self.period_for(token_type);
+            let cancel = self.replenish_cancel.clone();
+
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(period);
+                loop {
+                    tokio::select! {
+                        _ = interval.tick() => match limiter.upgrade() {
+                            Some(limiter) => limiter.replenish(token_type),
+                            None => break,
+                        },
+                        _ = cancel.cancelled() => break,
+                    }
+                }
+            });
+        }
+    }
+
+    fn period_for(&self, token_type: TokenType) -> Duration {
+        match token_type {
+            TokenType::Bytes => self.config_bytes.replenishment_period,
+            TokenType::Ops => self.config_ops.replenishment_period,
+        }
+    }
+
+    fn config_for(&self, token_type: TokenType) -> &BucketConfig {
+        match token_type {
+            TokenType::Bytes => &self.config_bytes,
+            TokenType::Ops => &self.config_ops,
+        }
+    }
+
+    fn bucket_mut<'a>(state: &'a mut State, token_type: TokenType) -> &'a mut BucketState {
+        match token_type {
+            TokenType::Bytes => &mut state.bytes,
+            TokenType::Ops => &mut state.ops,
+        }
+    }
+
+    /// Replenish one dimension's bucket and wake any queued requests that
+    /// can now be satisfied.
+    fn replenish(&self, token_type: TokenType) {
+        let mut state = self.state.lock();
+        if state.disposed {
+            return;
+        }
+
+        let config = self.config_for(token_type).clone();
+        let now = Instant::now();
+        let bucket = Self::bucket_mut(&mut state, token_type);
+        let elapsed = now.duration_since(bucket.last_replenished);
+        let periods = elapsed.as_secs_f64() / config.replenishment_period.as_secs_f64();
+        let tokens_to_add = periods * config.tokens_per_period as f64;
+
+        if tokens_to_add > 0.0 {
+            bucket.available = (bucket.available + tokens_to_add).min(config.token_limit as f64);
+            bucket.last_replenished = now;
+        }
+
+        self.update_idle_locked(&mut state);
+        self.process_queue_locked(&mut state);
+    }
+
+    fn update_idle_locked(&self, state: &mut State) {
+        let full = state.bytes.available >= self.config_bytes.token_limit as f64 - 0.001
+            && state.ops.available >= self.config_ops.token_limit as f64 - 0.001;
+
+        if full && state.idle_since.is_none() {
+            state.idle_since = Some(Instant::now());
+        }
+    }
+
+    /// Whether `state`'s buckets can currently afford `costs` without
+    /// debiting anything. Returns the first dimension found short, if any.
+    fn shortfall(state: &State, costs: &HashMap<TokenType, u32>) -> Option<TokenType> {
+        for (&token_type, &cost) in costs {
+            let available = match token_type {
+                TokenType::Bytes => state.bytes.available,
+                TokenType::Ops => state.ops.available,
+            };
+            if available < cost as f64 {
+                return Some(token_type);
+            }
+        }
+        None
+    }
+
+    /// How long a caller should wait before `costs` could be satisfied,
+    /// taking the longest of the two dimensions' individual wait times
+    /// (mirroring `CompositeTokenBucketLimiter::retry_after_locked`).
+    fn retry_after_locked(&self, state: &State, costs: &HashMap<TokenType, u32>) -> Duration {
+        costs
+            .iter()
+            .filter_map(|(&token_type, &cost)| {
+                let available = match token_type {
+                    TokenType::Bytes => state.bytes.available,
+                    TokenType::Ops => state.ops.available,
+                };
+                let tokens_needed = (cost as f64 - available).max(0.0);
+                if tokens_needed <= 0.0 {
+                    return None;
+                }
+                let config = self.config_for(token_type);
+                let periods_needed = (tokens_needed / config.tokens_per_period as f64).ceil() as u32;
+                Some(config.replenishment_period * periods_needed.max(1))
+            })
+            .max()
+            .unwrap_or(Duration::ZERO)
+    }
+
+    fn debit_locked(state: &mut State, costs: &HashMap<TokenType, u32>) {
+        for (&token_type, &cost) in costs {
+            Self::bucket_mut(state, token_type).available -= cost as f64;
+        }
+    }
+
+    fn process_queue_locked(&self, state: &mut State) {
+        loop {
+            let next_index = match self.queue_processing_order {
+                QueueProcessingOrder::OldestFirst => 0,
+                QueueProcessingOrder::NewestFirst => state.queue.len().saturating_sub(1),
+            };
+
+            let Some(next) = state.queue.get(next_index) else {
+                break;
+            };
+
+            if next.response.is_closed() {
+                if let Some(req) = state.queue.remove(next_index) {
+                    state.queue_count -= req.costs.values().sum::<u32>();
+                }
+                continue;
+            }
+
+            if Self::shortfall(state, &next.costs).is_some() {
+                break;
+            }
+
+            let req = state.queue.remove(next_index).unwrap();
+            state.queue_count -= req.costs.values().sum::<u32>();
+            Self::debit_locked(state, &req.costs);
+            state.idle_since = None;
+            let _ = req.response.send(Ok(RateLimitLease::success()));
+            self.successful_leases.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Attempt to acquire a per-dimension cost vector immediately, without
+    /// queueing. Succeeds only if every named dimension currently has
+    /// enough tokens; otherwise no bucket is debited and the returned lease
+    /// is failed, tagged with `"BlockedTokenType"` metadata naming the
+    /// dimension that came up short.
+    pub fn attempt_acquire_typed(
+        &self,
+        costs: &HashMap<TokenType, u32>,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        let mut state = self.state.lock();
+        if state.disposed {
+            return Err(RateLimitError::Disposed);
+        }
+
+        if let Some(blocked_by) = Self::shortfall(&state, costs) {
+            self.failed_leases.fetch_add(1, Ordering::Relaxed);
+            let retry_after = self.retry_after_locked(&state, costs);
+            return Ok(RateLimitLease::failed(Some(retry_after))
+                .with_metadata("BlockedTokenType", blocked_by));
+        }
+
+        Self::debit_locked(&mut state, costs);
+        state.idle_since = None;
+        self.successful_leases.fetch_add(1, Ordering::Relaxed);
+        Ok(RateLimitLease::success())
+    }
+
+    /// Acquire a per-dimension cost vector, queueing until both dimensions
+    /// can be satisfied together if either is currently short.
+    pub async fn acquire_async_typed(
+        &self,
+        costs: HashMap<TokenType, u32>,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        let rx = {
+            let mut state = self.state.lock();
+            if state.disposed {
+                return Err(RateLimitError::Disposed);
+            }
+
+            if Self::shortfall(&state, &costs).is_none() {
+                Self::debit_locked(&mut state, &costs);
+                state.idle_since = None;
+                self.successful_leases.fetch_add(1, Ordering::Relaxed);
+                return Ok(RateLimitLease::success());
+            }
+
+            let total_cost: u32 = costs.values().sum();
+            if state.queue_count + total_cost > self.queue_limit {
+                self.failed_leases.fetch_add(1, Ordering::Relaxed);
+                let retry_after = self.retry_after_locked(&state, &costs);
+                return Ok(RateLimitLease::failed(Some(retry_after)));
+            }
+
+            let (tx, rx) = oneshot::channel();
+            state.queue.push_back(QueuedRequest {
+                costs,
+                response: tx,
+                queued_at: Instant::now(),
+            });
+            state.queue_count += total_cost;
+            rx
+        };
+
+        if let Some(token) = cancel_token {
+            tokio::select! {
+                result = rx => result.unwrap_or(Err(RateLimitError::Cancelled)),
+                _ = token.cancelled() => Err(RateLimitError::Cancelled),
+            }
+        } else {
+            rx.await.unwrap_or(Err(RateLimitError::Cancelled))
+        }
+    }
+
+    /// Convenience form of `attempt_acquire_typed` that takes a cost list
+    /// instead of a `HashMap`, e.g.
+    /// `attempt_acquire_cost(&[(TokenType::Bytes, 4096), (TokenType::Ops, 1)])`.
+    ///
+    /// Dimensions are checked and debited together under a single lock (see
+    /// `attempt_acquire_typed`), so there's no partial-success case to roll
+    /// back - either every named dimension has enough tokens and all of
+    /// them are debited, or none are.
+    pub fn attempt_acquire_cost(
+        &self,
+        costs: &[(TokenType, u32)],
+    ) -> Result<RateLimitLease, RateLimitError> {
+        self.attempt_acquire_typed(&costs.iter().copied().collect())
+    }
+
+    /// Convenience form of `acquire_async_typed` that takes a cost list
+    /// instead of a `HashMap`.
+    pub async fn acquire_async_cost(
+        &self,
+        costs: &[(TokenType, u32)],
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        self.acquire_async_typed(costs.iter().copied().collect(), cancel_token)
+            .await
+    }
+}
+
+#[async_trait]
+impl RateLimiter for MultiTokenBucketLimiter {
+    fn attempt_acquire(&self, permit_count: u32) -> Result<RateLimitLease, RateLimitError> {
+        let costs = HashMap::from([(TokenType::Bytes, permit_count), (TokenType::Ops, permit_count)]);
+        self.attempt_acquire_typed(&costs)
+    }
+
+    async fn acquire_async(
+        &self,
+        permit_count: u32,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        let costs = HashMap::from([(TokenType::Bytes, permit_count), (TokenType::Ops, permit_count)]);
+        self.acquire_async_typed(costs, cancel_token).await
+    }
+
+    fn get_statistics(&self) -> RateLimiterStatistics {
+        let state = self.state.lock();
+        let available = state.bytes.available.min(state.ops.available) as i64;
+
+        RateLimiterStatistics::new(
+            available,
+            state.queue_count,
+            self.successful_leases.load(Ordering::Relaxed),
+            self.failed_leases.load(Ordering::Relaxed),
+        )
+    }
+
+    fn idle_duration(&self) -> Option<Duration> {
+        let state = self.state.lock();
+        state.idle_since.map(|since| since.elapsed())
+    }
+}
+
+impl Drop for MultiTokenBucketLimiter {
+    fn drop(&mut self) {
+        self.replenish_cancel.cancel();
+
+        let mut state = self.state.lock();
+        state.disposed = true;
+        while let Some(request) = state.queue.pop_front() {
+            state.queue_count -= request.costs.values().sum::<u32>();
+            let _ = request.response.send(Ok(RateLimitLease::failed(None)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(bytes_limit: u32, ops_limit: u32, queue_limit: u32) -> MultiTokenBucketLimiterOptions {
+        MultiTokenBucketLimiterOptions {
+            bytes: BucketConfig::new(bytes_limit, bytes_limit, Duration::from_secs(60)).unwrap(),
+            ops: BucketConfig::new(ops_limit, ops_limit, Duration::from_secs(60)).unwrap(),
+            queue_limit,
+            queue_processing_order: QueueProcessingOrder::OldestFirst,
+        }
+    }
+
+    #[test]
+    fn test_acquires_when_both_dimensions_afford_cost() {
+        let limiter = MultiTokenBucketLimiter::new(options(100, 5, 10));
+
+        let costs = HashMap::from([(TokenType::Bytes, 50), (TokenType::Ops, 1)]);
+        let lease = limiter.attempt_acquire_typed(&costs).unwrap();
+        assert!(lease.is_acquired());
+    }
+
+    #[test]
+    fn test_attempt_acquire_cost_matches_typed_equivalent() {
+        let limiter = MultiTokenBucketLimiter::new(options(100, 5, 10));
+
+        let lease = limiter
+            .attempt_acquire_cost(&[(TokenType::Bytes, 50), (TokenType::Ops, 1)])
+            .unwrap();
+        assert!(lease.is_acquired());
+
+        // The cost list and the equivalent HashMap must debit identically.
+        let remaining = limiter
+            .attempt_acquire_typed(&HashMap::from([(TokenType::Bytes, 50), (TokenType::Ops, 4)]))
+            .unwrap();
+        assert!(remaining.is_acquired());
+    }
+
+    #[test]
+    fn test_fails_without_debiting_either_dimension() {
+        let limiter = MultiTokenBucketLimiter::new(options(100, 2, 10));
+
+        let costs = HashMap::from([(TokenType::Bytes, 10), (TokenType::Ops, 10)]); // ops exceeds its limit of 2
+        let lease = limiter.attempt_acquire_typed(&costs).unwrap();
+        assert!(!lease.is_acquired());
+        assert_eq!(
+            lease.try_get_metadata::<TokenType>("BlockedTokenType"),
+            Some(&TokenType::Ops)
+        );
+
+        // "bytes" must still be untouched since the request failed.
+        let bytes_only = HashMap::from([(TokenType::Bytes, 100)]);
+        let lease2 = limiter.attempt_acquire_typed(&bytes_only).unwrap();
+        assert!(lease2.is_acquired());
+    }
+
+    #[test]
+    fn test_failed_lease_reports_max_retry_after_across_dimensions() {
+        let mut opts = options(100, 100, 10);
+        // ops: 1 token available, replenishing 1 token every 10s - needs
+        // 9 more tokens, so 9 periods (90s) to afford a cost of 10.
+        opts.ops = BucketConfig::new(1, 1, Duration::from_secs(10)).unwrap();
+        let limiter = MultiTokenBucketLimiter::new(opts);
+
+        let costs = HashMap::from([(TokenType::Bytes, 1), (TokenType::Ops, 10)]);
+        let lease = limiter.attempt_acquire_typed(&costs).unwrap();
+        assert!(!lease.is_acquired());
+        assert_eq!(lease.retry_after(), Some(Duration::from_secs(90)));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_async_queues_until_both_dimensions_free_up() {
+        let mut opts = options(100, 1, 10);
+        opts.ops = BucketConfig::new(1, 1, Duration::from_millis(30)).unwrap();
+        let limiter = Arc::new(MultiTokenBucketLimiter::new(opts));
+        limiter.start_auto_replenishment();
+
+        // Exhaust the "ops" bucket.
+        let costs = HashMap::from([(TokenType::Bytes, 1), (TokenType::Ops, 1)]);
+        let lease1 = limiter.attempt_acquire_typed(&costs).unwrap();
+        assert!(lease1.is_acquired());
+
+        let waiter = {
+            let limiter = Arc::clone(&limiter);
+            tokio::spawn(async move {
+                limiter
+                    .acquire_async_typed(
+                        HashMap::from([(TokenType::Bytes, 1), (TokenType::Ops, 1)]),
+                        None,
+                    )
+                    .await
+            })
+        };
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!waiter.is_finished());
+
+        let lease2 = tokio::time::timeout(Duration::from_millis(200), waiter)
+            .await
+            .expect("queued request should resolve once the ops bucket replenishes")
+            .unwrap()
+            .unwrap();
+        assert!(lease2.is_acquired());
+    }
+
+    #[test]
+    fn test_uniform_attempt_acquire_applies_cost_to_both_dimensions() {
+        let limiter = MultiTokenBucketLimiter::new(options(100, 2, 10));
+
+        // "ops" has only 2 tokens, so a uniform cost of 2 succeeds once.
+        let lease1 = limiter.attempt_acquire(2).unwrap();
+        assert!(lease1.is_acquired());
+        let lease2 = limiter.attempt_acquire(2).unwrap();
+        assert!(!lease2.is_acquired());
+    }
+}
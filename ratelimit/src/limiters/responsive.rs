@@ -0,0 +1,315 @@
+//! Responsive token-bucket wrapper that adapts to observed server feedback.
+//!
+//! Inspired by Riven's responsive rate limiting against live Discord API
+//! rate-limit headers, `ResponsiveTokenBucketLimiter` wraps a
+//! `TokenBucketRateLimiter` and steers its `tokens_per_period` toward
+//! whatever rate the downstream server is actually enforcing, rather than
+//! relying on a hardcoded, conservative static limit. It runs a simple AIMD
+//! loop on top of `TokenBucketRateLimiter::update()`: `record_rejection`
+//! multiplicatively halves the current rate (never below 1) and suppresses
+//! climb-back for the reported `retry_after`, while `record_success`
+//! additively climbs the rate back toward a configured ceiling once a
+//! sustained run of successes is observed. Because both adjustments go
+//! through `update()`, in-flight leases and queued waiters are never
+//! disrupted.
+
+use crate::core::traits::RateLimiter;
+use crate::core::{RateLimitError, RateLimitLease, RateLimiterStatistics};
+use crate::limiters::token_bucket::{BucketUpdate, TokenBucketRateLimiter};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// Options for configuring a `ResponsiveTokenBucketLimiter`.
+#[derive(Clone, Debug)]
+pub struct ResponsiveTokenBucketLimiterOptions {
+    /// Upper bound `tokens_per_period` is allowed to climb back to.
+    pub max_tokens_per_period: u32,
+
+    /// Number of consecutive `record_success()` calls (since the last
+    /// rejection or rate increase) required before `tokens_per_period` is
+    /// additively increased again.
+    pub success_window: u32,
+
+    /// Additive step applied to `tokens_per_period` once `success_window`
+    /// consecutive successes have been observed.
+    pub increase_step: u32,
+}
+
+impl ResponsiveTokenBucketLimiterOptions {
+    /// Create new responsive limiter options with validation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `max_tokens_per_period`, `success_window`, or
+    /// `increase_step` is 0.
+    pub fn new(
+        max_tokens_per_period: u32,
+        success_window: u32,
+        increase_step: u32,
+    ) -> Result<Self, RateLimitError> {
+        if max_tokens_per_period == 0 {
+            return Err(RateLimitError::InvalidParameter(
+                "max_tokens_per_period must be greater than 0".to_string(),
+            ));
+        }
+
+        if success_window == 0 {
+            return Err(RateLimitError::InvalidParameter(
+                "success_window must be greater than 0".to_string(),
+            ));
+        }
+
+        if increase_step == 0 {
+            return Err(RateLimitError::InvalidParameter(
+                "increase_step must be greater than 0".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            max_tokens_per_period,
+            success_window,
+            increase_step,
+        })
+    }
+}
+
+/// AIMD bookkeeping for the adaptive loop, separate from the wrapped
+/// bucket's own state.
+struct AdaptiveState {
+    /// Consecutive successes observed since the last rejection or increase.
+    success_streak: u32,
+
+    /// While `Some` and still in the future, `record_success()` is a no-op:
+    /// the rate stays at its post-rejection value for the reported
+    /// `retry_after` instead of immediately starting to climb back up.
+    stalled_until: Option<Instant>,
+}
+
+/// Wraps a `TokenBucketRateLimiter` and adapts its `tokens_per_period`
+/// toward the true server-enforced rate using feedback from downstream
+/// calls.
+///
+/// # Example
+///
+/// ```no_run
+/// use ratelimit::limiters::{
+///     ResponsiveTokenBucketLimiter, ResponsiveTokenBucketLimiterOptions,
+///     TokenBucketRateLimiter, TokenBucketRateLimiterOptions,
+/// };
+/// use ratelimit::core::QueueProcessingOrder;
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let inner = Arc::new(TokenBucketRateLimiter::new(TokenBucketRateLimiterOptions::new(
+///     100, 100, Duration::from_secs(1), 0, QueueProcessingOrder::OldestFirst, true, 0, false,
+/// ).unwrap()).unwrap());
+///
+/// let limiter = ResponsiveTokenBucketLimiter::new(
+///     inner,
+///     ResponsiveTokenBucketLimiterOptions::new(100, 10, 5).unwrap(),
+/// );
+///
+/// // The downstream API rejected us with a 30-second Retry-After header.
+/// limiter.record_rejection(Duration::from_secs(30));
+/// assert_eq!(limiter.current_tokens_per_period(), 50);
+/// ```
+pub struct ResponsiveTokenBucketLimiter {
+    inner: Arc<TokenBucketRateLimiter>,
+    options: ResponsiveTokenBucketLimiterOptions,
+    state: Mutex<AdaptiveState>,
+}
+
+impl ResponsiveTokenBucketLimiter {
+    /// Wrap `inner` in an adaptive limiter governed by `options`.
+    pub fn new(
+        inner: Arc<TokenBucketRateLimiter>,
+        options: ResponsiveTokenBucketLimiterOptions,
+    ) -> Self {
+        Self {
+            inner,
+            options,
+            state: Mutex::new(AdaptiveState {
+                success_streak: 0,
+                stalled_until: None,
+            }),
+        }
+    }
+
+    /// Access the wrapped limiter.
+    pub fn inner(&self) -> &Arc<TokenBucketRateLimiter> {
+        &self.inner
+    }
+
+    /// The currently adapted `tokens_per_period`, reflecting all
+    /// `record_rejection`/`record_success` adjustments so far.
+    pub fn current_tokens_per_period(&self) -> u32 {
+        self.inner.tokens_per_period()
+    }
+
+    /// Report a downstream rejection (e.g. an HTTP 429) carrying a
+    /// server-reported `retry_after`.
+    ///
+    /// Multiplicatively halves `tokens_per_period` (never below 1) and
+    /// resets the success streak, suppressing any climb-back via
+    /// `record_success` until `retry_after` has elapsed.
+    pub fn record_rejection(&self, retry_after: Duration) {
+        let mut state = self.state.lock();
+        state.success_streak = 0;
+        state.stalled_until = Some(Instant::now() + retry_after);
+        drop(state);
+
+        let halved = (self.inner.tokens_per_period() / 2).max(1);
+        let _ = self.inner.update(BucketUpdate {
+            tokens_per_period: Some(halved),
+            ..Default::default()
+        });
+    }
+
+    /// Report a successful downstream call.
+    ///
+    /// A no-op while a prior rejection's `retry_after` stall is still in
+    /// effect. Otherwise, once `success_window` consecutive successes have
+    /// accumulated, additively increases `tokens_per_period` by
+    /// `increase_step`, clamped to `max_tokens_per_period`.
+    pub fn record_success(&self) {
+        let mut state = self.state.lock();
+
+        if let Some(stalled_until) = state.stalled_until {
+            if Instant::now() < stalled_until {
+                return;
+            }
+            state.stalled_until = None;
+        }
+
+        state.success_streak += 1;
+        if state.success_streak < self.options.success_window {
+            return;
+        }
+        state.success_streak = 0;
+        drop(state);
+
+        let current = self.inner.tokens_per_period();
+        let increased = (current + self.options.increase_step).min(self.options.max_tokens_per_period);
+        if increased != current {
+            let _ = self.inner.update(BucketUpdate {
+                tokens_per_period: Some(increased),
+                ..Default::default()
+            });
+        }
+    }
+}
+
+#[async_trait]
+impl RateLimiter for ResponsiveTokenBucketLimiter {
+    fn attempt_acquire(&self, permit_count: u32) -> Result<RateLimitLease, RateLimitError> {
+        self.inner.attempt_acquire(permit_count)
+    }
+
+    async fn acquire_async(
+        &self,
+        permit_count: u32,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        self.inner.acquire_async(permit_count, cancel_token).await
+    }
+
+    fn get_statistics(&self) -> RateLimiterStatistics {
+        self.inner.get_statistics()
+    }
+
+    fn idle_duration(&self) -> Option<Duration> {
+        self.inner.idle_duration()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::QueueProcessingOrder;
+    use crate::limiters::token_bucket::TokenBucketRateLimiterOptions;
+
+    fn make_inner(tokens_per_period: u32) -> Arc<TokenBucketRateLimiter> {
+        let options = TokenBucketRateLimiterOptions::new(
+            tokens_per_period,
+            tokens_per_period,
+            Duration::from_secs(1),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false, // auto_replenishment - manual mode, we drive updates ourselves
+            0,     // one_time_burst
+            false, // replenish_fractionally
+        )
+        .unwrap();
+
+        Arc::new(TokenBucketRateLimiter::new(options).unwrap())
+    }
+
+    #[test]
+    fn test_record_rejection_halves_rate_and_never_goes_below_one() {
+        let limiter = ResponsiveTokenBucketLimiter::new(
+            make_inner(100),
+            ResponsiveTokenBucketLimiterOptions::new(100, 5, 10).unwrap(),
+        );
+
+        limiter.record_rejection(Duration::from_millis(10));
+        assert_eq!(limiter.current_tokens_per_period(), 50);
+
+        limiter.record_rejection(Duration::from_millis(10));
+        assert_eq!(limiter.current_tokens_per_period(), 25);
+
+        // Repeated halving floors at 1, never 0.
+        for _ in 0..10 {
+            limiter.record_rejection(Duration::from_millis(10));
+        }
+        assert_eq!(limiter.current_tokens_per_period(), 1);
+    }
+
+    #[test]
+    fn test_record_success_climbs_back_after_success_window() {
+        let limiter = ResponsiveTokenBucketLimiter::new(
+            make_inner(100),
+            ResponsiveTokenBucketLimiterOptions::new(100, 3, 10).unwrap(),
+        );
+
+        limiter.record_rejection(Duration::from_millis(0));
+        assert_eq!(limiter.current_tokens_per_period(), 50);
+
+        // Stall has already elapsed (zero retry_after), so successes count.
+        limiter.record_success();
+        limiter.record_success();
+        assert_eq!(limiter.current_tokens_per_period(), 50);
+
+        limiter.record_success();
+        assert_eq!(limiter.current_tokens_per_period(), 60);
+    }
+
+    #[test]
+    fn test_record_success_is_suppressed_during_retry_after_stall() {
+        let limiter = ResponsiveTokenBucketLimiter::new(
+            make_inner(100),
+            ResponsiveTokenBucketLimiterOptions::new(100, 1, 10).unwrap(),
+        );
+
+        limiter.record_rejection(Duration::from_secs(60));
+        assert_eq!(limiter.current_tokens_per_period(), 50);
+
+        // success_window is 1, but the stall should suppress the increase
+        // even though a single success would otherwise trigger it.
+        limiter.record_success();
+        assert_eq!(limiter.current_tokens_per_period(), 50);
+    }
+
+    #[test]
+    fn test_increase_clamps_to_max_tokens_per_period() {
+        let limiter = ResponsiveTokenBucketLimiter::new(
+            make_inner(95),
+            ResponsiveTokenBucketLimiterOptions::new(100, 1, 10).unwrap(),
+        );
+
+        limiter.record_success();
+        assert_eq!(limiter.current_tokens_per_period(), 100);
+    }
+}
@@ -0,0 +1,297 @@
+//! Server-feedback wrapper limiter.
+//!
+//! Wraps an inner rate limiter and reconciles its local view of available
+//! permits with a downstream dependency's own self-reported rate limit
+//! state - parsed from response headers such as `X-RateLimit-Remaining`,
+//! `X-RateLimit-Reset`, and `Retry-After`. Header parsing is deliberately
+//! kept out of this type (and the crate as a whole): callers hand in an
+//! already-parsed `RateLimitHeaders`, so no HTTP dependency leaks in here.
+
+use crate::core::{RateLimitError, RateLimitLease, RateLimiter, RateLimiterStatistics};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+/// Parsed rate-limit feedback from a downstream API response.
+///
+/// This carries whatever a caller's HTTP client already extracted from
+/// response headers - it has no notion of headers, requests, or transport
+/// itself, so the core crate stays transport-agnostic.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RateLimitHeaders {
+    /// Permits the server reports as still available for its current
+    /// window (e.g. parsed from `X-RateLimit-Remaining`).
+    pub remaining: Option<u32>,
+    /// When the server's window resets, if advertised (e.g. parsed from
+    /// `X-RateLimit-Reset`). Currently informational only; reconciliation
+    /// relies on `remaining` and `retry_after`.
+    pub reset_at: Option<Instant>,
+    /// `Retry-After` hint: block all acquisitions until this long has
+    /// elapsed.
+    pub retry_after: Option<Duration>,
+}
+
+/// A rate limiter decorator that reconciles its view of available permits
+/// against a downstream dependency's self-reported rate limit state, so
+/// multiple overlapping server-side buckets can be tracked per key instead
+/// of relying solely on the local estimate (and risking a 429).
+///
+/// # Example
+///
+/// ```no_run
+/// use ratelimit::limiters::{ServerFeedbackLimiter, RateLimitHeaders, TokenBucketRateLimiter, TokenBucketRateLimiterOptions};
+/// use ratelimit::core::{RateLimiter, QueueProcessingOrder};
+///
+/// let inner = TokenBucketRateLimiter::new(TokenBucketRateLimiterOptions::new(
+///     100, 100, std::time::Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false, 0, false,
+/// ).unwrap()).unwrap();
+/// let limiter = ServerFeedbackLimiter::new(inner);
+///
+/// // The server says only 2 requests are left in its own window, tighter
+/// // than our local estimate of 100.
+/// limiter.update_from_headers(RateLimitHeaders { remaining: Some(2), ..Default::default() });
+/// assert_eq!(limiter.get_statistics().current_available_permits, 2);
+/// ```
+pub struct ServerFeedbackLimiter<L: RateLimiter> {
+    inner: L,
+    /// Server-reported remaining-permit ceiling, if the server has told us
+    /// something more restrictive than our own local estimate.
+    server_remaining: Mutex<Option<u32>>,
+    freeze_until: Mutex<Option<Instant>>,
+    notify: Notify,
+}
+
+impl<L: RateLimiter> ServerFeedbackLimiter<L> {
+    /// Wrap `inner` in a limiter that can be reconciled via
+    /// `update_from_headers`.
+    pub fn new(inner: L) -> Self {
+        Self {
+            inner,
+            server_remaining: Mutex::new(None),
+            freeze_until: Mutex::new(None),
+            notify: Notify::new(),
+        }
+    }
+
+    /// Access the wrapped limiter.
+    pub fn inner(&self) -> &L {
+        &self.inner
+    }
+
+    /// Reconcile this limiter's local view with a parsed server response.
+    ///
+    /// If `headers.remaining` is lower than what this limiter currently
+    /// believes is available, the local view is shrunk to match - the
+    /// server's count is treated as authoritative since it reflects shared
+    /// or overlapping quota this limiter can't otherwise observe. If
+    /// `remaining` is at or above the local estimate, any previously
+    /// recorded server ceiling is cleared (the server is no longer more
+    /// restrictive than we are). A `retry_after` hint freezes all
+    /// acquisitions until it elapses, regardless of permit counts,
+    /// following the same extend-never-shorten rule as `FreezingLimiter`.
+    pub fn update_from_headers(&self, headers: RateLimitHeaders) {
+        if let Some(remaining) = headers.remaining {
+            let local_estimate = self.inner.get_statistics().current_available_permits.max(0) as u32;
+            let mut server_remaining = self.server_remaining.lock();
+            *server_remaining = if remaining < local_estimate { Some(remaining) } else { None };
+        }
+
+        if let Some(retry_after) = headers.retry_after {
+            let until = Instant::now() + retry_after;
+            let mut freeze_until = self.freeze_until.lock();
+            let should_update = match *freeze_until {
+                Some(existing) => until > existing,
+                None => true,
+            };
+            if should_update {
+                *freeze_until = Some(until);
+            }
+        }
+
+        self.notify.notify_waiters();
+    }
+
+    /// Returns `true` if a `Retry-After` hint is currently in effect.
+    pub fn is_frozen(&self) -> bool {
+        self.remaining_freeze().is_some()
+    }
+
+    /// The server-reported remaining-permit ceiling currently in effect, or
+    /// `None` if the server hasn't told us anything more restrictive than
+    /// our own estimate.
+    pub fn server_remaining(&self) -> Option<u32> {
+        *self.server_remaining.lock()
+    }
+
+    fn remaining_freeze(&self) -> Option<Duration> {
+        let freeze_until = *self.freeze_until.lock();
+        freeze_until.and_then(|until| until.checked_duration_since(Instant::now()))
+    }
+
+    /// Counts a granted lease against the server-reported ceiling, if one is
+    /// in effect, so repeated local grants can't outrun what the server told
+    /// us was left.
+    fn debit_server_remaining(&self, permit_count: u32) {
+        let mut server_remaining = self.server_remaining.lock();
+        if let Some(remaining) = *server_remaining {
+            *server_remaining = Some(remaining.saturating_sub(permit_count));
+        }
+    }
+}
+
+#[async_trait]
+impl<L: RateLimiter> RateLimiter for ServerFeedbackLimiter<L> {
+    fn attempt_acquire(&self, permit_count: u32) -> Result<RateLimitLease, RateLimitError> {
+        if let Some(remaining) = self.remaining_freeze() {
+            return Ok(RateLimitLease::failed(Some(remaining)));
+        }
+        if let Some(server_remaining) = self.server_remaining() {
+            if permit_count > server_remaining {
+                return Ok(RateLimitLease::failed(None));
+            }
+        }
+
+        let lease = self.inner.attempt_acquire(permit_count)?;
+        if lease.is_acquired() {
+            self.debit_server_remaining(permit_count);
+        }
+        Ok(lease)
+    }
+
+    async fn acquire_async(
+        &self,
+        permit_count: u32,
+        cancel_token: Option<CancellationToken>,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        let cancel = cancel_token.clone().unwrap_or_default();
+
+        loop {
+            let Some(remaining) = self.remaining_freeze() else {
+                break;
+            };
+
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            tokio::select! {
+                _ = tokio::time::sleep(remaining) => {}
+                _ = &mut notified => {}
+                _ = cancel.cancelled() => return Err(RateLimitError::Cancelled),
+            }
+        }
+
+        if let Some(server_remaining) = self.server_remaining() {
+            if permit_count > server_remaining {
+                return Ok(RateLimitLease::failed(None));
+            }
+        }
+
+        let lease = self.inner.acquire_async(permit_count, cancel_token).await?;
+        if lease.is_acquired() {
+            self.debit_server_remaining(permit_count);
+        }
+        Ok(lease)
+    }
+
+    fn get_statistics(&self) -> RateLimiterStatistics {
+        let mut stats = self.inner.get_statistics();
+        if let Some(server_remaining) = self.server_remaining() {
+            stats.current_available_permits = stats.current_available_permits.min(server_remaining as i64);
+        }
+        stats
+    }
+
+    fn idle_duration(&self) -> Option<Duration> {
+        self.inner.idle_duration()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::limiters::{ConcurrencyLimiter, ConcurrencyLimiterOptions};
+    use crate::QueueProcessingOrder;
+
+    fn unlimited_inner() -> ConcurrencyLimiter {
+        ConcurrencyLimiter::new(ConcurrencyLimiterOptions {
+            permit_limit: 10,
+            queue_limit: 10,
+            queue_processing_order: QueueProcessingOrder::OldestFirst,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_server_remaining_shrinks_local_view() {
+        let limiter = ServerFeedbackLimiter::new(unlimited_inner());
+        assert_eq!(limiter.get_statistics().current_available_permits, 10);
+
+        limiter.update_from_headers(RateLimitHeaders {
+            remaining: Some(2),
+            ..Default::default()
+        });
+        assert_eq!(limiter.get_statistics().current_available_permits, 2);
+        assert_eq!(limiter.server_remaining(), Some(2));
+    }
+
+    #[test]
+    fn test_server_remaining_cleared_when_not_more_restrictive() {
+        let limiter = ServerFeedbackLimiter::new(unlimited_inner());
+        limiter.update_from_headers(RateLimitHeaders {
+            remaining: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(limiter.server_remaining(), Some(1));
+
+        limiter.update_from_headers(RateLimitHeaders {
+            remaining: Some(10),
+            ..Default::default()
+        });
+        assert_eq!(limiter.server_remaining(), None);
+    }
+
+    #[test]
+    fn test_attempt_acquire_respects_server_ceiling() {
+        let limiter = ServerFeedbackLimiter::new(unlimited_inner());
+        limiter.update_from_headers(RateLimitHeaders {
+            remaining: Some(1),
+            ..Default::default()
+        });
+
+        let lease1 = limiter.attempt_acquire(1).unwrap();
+        assert!(lease1.is_acquired());
+
+        let lease2 = limiter.attempt_acquire(1).unwrap();
+        assert!(!lease2.is_acquired());
+    }
+
+    #[test]
+    fn test_retry_after_freezes_regardless_of_server_remaining() {
+        let limiter = ServerFeedbackLimiter::new(unlimited_inner());
+        limiter.update_from_headers(RateLimitHeaders {
+            retry_after: Some(Duration::from_secs(60)),
+            ..Default::default()
+        });
+
+        let lease = limiter.attempt_acquire(1).unwrap();
+        assert!(!lease.is_acquired());
+        assert!(lease.retry_after().unwrap() > Duration::from_secs(0));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_async_waits_out_the_freeze_then_retries_inner() {
+        let limiter = std::sync::Arc::new(ServerFeedbackLimiter::new(unlimited_inner()));
+        limiter.update_from_headers(RateLimitHeaders {
+            retry_after: Some(Duration::from_millis(30)),
+            ..Default::default()
+        });
+
+        let result = tokio::time::timeout(Duration::from_millis(200), limiter.acquire_async(1, None))
+            .await
+            .expect("acquire_async should resolve once the freeze elapses")
+            .unwrap();
+        assert!(result.is_acquired());
+        assert!(!limiter.is_frozen());
+    }
+}
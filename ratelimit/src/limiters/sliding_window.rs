@@ -2,11 +2,12 @@ use crate::core::{
     QueueProcessingOrder, RateLimitError, RateLimitLease, RateLimiter, RateLimiterStatistics,
     ReplenishingRateLimiter,
 };
+use crate::utils::{Clock, SystemClock};
 use async_trait::async_trait;
 use std::{
     collections::VecDeque,
     sync::{
-        atomic::{AtomicBool, AtomicU32, Ordering},
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
         Arc, Mutex,
     },
     time::{Duration, Instant},
@@ -18,29 +19,34 @@ use tokio_util::sync::CancellationToken;
 pub struct SlidingWindowRateLimiter {
     config: SlidingWindowRateLimiterOptions,
     state: Arc<Mutex<State>>,
-    requests_tx: mpsc::UnboundedSender<Request>,
+    requests_tx: mpsc::Sender<Request>,
     total_successful_leases: Arc<AtomicU32>,
     total_failed_leases: Arc<AtomicU32>,
     processing_scheduled: Arc<AtomicBool>,
+    next_queue_id: Arc<AtomicU64>,
 }
 
 impl SlidingWindowRateLimiter {
     /// Creates a new sliding window rate limiter
     pub fn new(options: SlidingWindowRateLimiterOptions) -> Self {
-        let (tx, mut rx) = mpsc::unbounded_channel::<Request>();
+        let (tx, mut rx) = mpsc::channel::<Request>(options.request_channel_capacity);
 
         let state = Arc::new(Mutex::new(State {
             segments: VecDeque::new(),
             queue: VecDeque::new(),
             queue_count: 0,
-            idle_since: Some(Instant::now()),
+            idle_since: Some(options.clock.now()),
             auto_replenish_timer: None,
             disposed: false,
+            queued_lease_count: 0,
+            total_queue_wait_time: Duration::ZERO,
+            max_queue_wait_time: Duration::ZERO,
         }));
 
         let total_successful_leases = Arc::new(AtomicU32::new(0));
         let total_failed_leases = Arc::new(AtomicU32::new(0));
         let processing_scheduled = Arc::new(AtomicBool::new(false));
+        let next_queue_id = Arc::new(AtomicU64::new(0));
 
         let limiter = Self {
             config: options.clone(),
@@ -49,6 +55,7 @@ impl SlidingWindowRateLimiter {
             total_successful_leases: total_successful_leases.clone(),
             total_failed_leases: total_failed_leases.clone(),
             processing_scheduled: processing_scheduled.clone(),
+            next_queue_id,
         };
 
         // Start background request processor
@@ -99,12 +106,13 @@ impl SlidingWindowRateLimiter {
     ) {
         match request {
             Request::AcquireAsync {
+                id,
                 permit_count,
                 response,
                 _cancel_token,
             } => {
                 // Try immediate acquisition
-                let result = Self::try_acquire_or_queue(permit_count, state, config);
+                let result = Self::try_acquire_or_queue(id, permit_count, state, config);
                 match result {
                     AcquireResult::Immediate(lease) => {
                         if lease.is_acquired() {
@@ -145,10 +153,19 @@ impl SlidingWindowRateLimiter {
                 let result = Self::replenish_core(state, config, processing_scheduled);
                 let _ = response.send(result);
             }
+            Request::Cancel { id } => {
+                let mut s = state.lock().unwrap();
+                if let Some(idx) = s.queue.iter().position(|req| req.id == id) {
+                    if let Some(req) = s.queue.remove(idx) {
+                        s.queue_count = s.queue_count.saturating_sub(req.permits_requested);
+                    }
+                }
+            }
         }
     }
 
     fn try_acquire_or_queue(
+        id: u64,
         permit_count: u32,
         state: &Arc<Mutex<State>>,
         config: &SlidingWindowRateLimiterOptions,
@@ -179,21 +196,22 @@ impl SlidingWindowRateLimiter {
         if s.queue_count + permit_count <= config.queue_limit {
             let (tx, rx) = oneshot::channel();
             s.queue.push_back(QueuedRequest {
+                id,
                 permits_requested: permit_count,
                 response: tx,
-                queued_at: Instant::now(),
+                queued_at: config.clock.now(),
             });
             s.queue_count += permit_count;
             s.idle_since = None;
             AcquireResult::Queued(rx)
         } else {
-            let retry_after = Self::calculate_retry_after(&s);
+            let retry_after = Self::calculate_retry_after(&s, config);
             AcquireResult::Immediate(RateLimitLease::failed(Some(retry_after)))
         }
     }
 
-    fn remove_expired_segments(state: &mut State, _config: &SlidingWindowRateLimiterOptions) {
-        let now = Instant::now();
+    fn remove_expired_segments(state: &mut State, config: &SlidingWindowRateLimiterOptions) {
+        let now = config.clock.now();
         while let Some(segment) = state.segments.front() {
             if segment.expires_at <= now {
                 state.segments.pop_front();
@@ -213,7 +231,7 @@ impl SlidingWindowRateLimiter {
         count: u32,
         config: &SlidingWindowRateLimiterOptions,
     ) {
-        let now = Instant::now();
+        let now = config.clock.now();
 
         // Add to most recent segment if it exists and was created recently
         if let Some(last) = state.segments.back_mut() {
@@ -253,14 +271,38 @@ impl SlidingWindowRateLimiter {
 
         // Update idle time if queue is empty
         if s.queue.is_empty() && s.idle_since.is_none() {
-            s.idle_since = Some(Instant::now());
+            s.idle_since = Some(config.clock.now());
         }
 
         processing_scheduled.store(false, Ordering::Relaxed);
         processed > 0
     }
 
+    /// Fails and removes any queued request that has waited longer than
+    /// `config.max_queue_duration`, reclaiming its `queue_count` slot. No-op
+    /// if the option is unset.
+    fn expire_stale_queued_requests(state: &mut State, config: &SlidingWindowRateLimiterOptions) {
+        let Some(max_queue_duration) = config.max_queue_duration else {
+            return;
+        };
+
+        let now = config.clock.now();
+        let mut idx = 0;
+        while idx < state.queue.len() {
+            if now.duration_since(state.queue[idx].queued_at) > max_queue_duration {
+                let request = state.queue.remove(idx).unwrap();
+                state.queue_count = state.queue_count.saturating_sub(request.permits_requested);
+                let retry_after = Self::calculate_retry_after(state, config);
+                let _ = request.response.send(Ok(RateLimitLease::failed(Some(retry_after))));
+            } else {
+                idx += 1;
+            }
+        }
+    }
+
     fn process_queue(state: &mut State, config: &SlidingWindowRateLimiterOptions) -> u32 {
+        Self::expire_stale_queued_requests(state, config);
+
         let mut permits_granted = 0;
         let mut granted_requests = Vec::new();
 
@@ -299,6 +341,12 @@ impl SlidingWindowRateLimiter {
         for (idx, _) in granted_requests {
             if let Some(request) = state.queue.remove(idx) {
                 state.queue_count = state.queue_count.saturating_sub(request.permits_requested);
+
+                let wait = config.clock.now().duration_since(request.queued_at);
+                state.queued_lease_count += 1;
+                state.total_queue_wait_time += wait;
+                state.max_queue_wait_time = state.max_queue_wait_time.max(wait);
+
                 let _ = request.response.send(Ok(RateLimitLease::success()));
             }
         }
@@ -344,6 +392,7 @@ impl SlidingWindowRateLimiter {
             if self
                 .requests_tx
                 .send(Request::TryReplenish { response: tx })
+                .await
                 .is_err()
             {
                 break;
@@ -356,10 +405,10 @@ impl SlidingWindowRateLimiter {
         }
     }
 
-    fn calculate_retry_after(state: &State) -> Duration {
+    fn calculate_retry_after(state: &State, config: &SlidingWindowRateLimiterOptions) -> Duration {
         // Time until the oldest segment expires
         if let Some(oldest) = state.segments.front() {
-            let now = Instant::now();
+            let now = config.clock.now();
             if oldest.expires_at > now {
                 oldest.expires_at - now
             } else {
@@ -381,6 +430,7 @@ impl Clone for SlidingWindowRateLimiter {
             total_successful_leases: self.total_successful_leases.clone(),
             total_failed_leases: self.total_failed_leases.clone(),
             processing_scheduled: self.processing_scheduled.clone(),
+            next_queue_id: self.next_queue_id.clone(),
         }
     }
 }
@@ -413,7 +463,7 @@ impl RateLimiter for SlidingWindowRateLimiter {
             Ok(RateLimitLease::success())
         } else {
             // Calculate retry-after duration
-            let retry_after = Self::calculate_retry_after(&s);
+            let retry_after = Self::calculate_retry_after(&s, &self.config);
             self.total_failed_leases
                 .fetch_add(permit_count, Ordering::Relaxed);
             Ok(RateLimitLease::failed(Some(retry_after)))
@@ -425,14 +475,23 @@ impl RateLimiter for SlidingWindowRateLimiter {
         permit_count: u32,
         cancel_token: Option<CancellationToken>,
     ) -> Result<RateLimitLease, RateLimitError> {
+        let id = self.next_queue_id.fetch_add(1, Ordering::Relaxed);
         let (tx, rx) = oneshot::channel();
-        self.requests_tx
-            .send(Request::AcquireAsync {
-                permit_count,
-                response: tx,
-                _cancel_token: cancel_token.clone(),
-            })
+
+        // Reserve a slot before building the request so a saturated
+        // dispatcher applies backpressure to the caller instead of this
+        // limiter silently buffering unbounded `Request`s in memory.
+        let permit = self
+            .requests_tx
+            .reserve()
+            .await
             .map_err(|_| RateLimitError::Disposed)?;
+        permit.send(Request::AcquireAsync {
+            id,
+            permit_count,
+            response: tx,
+            _cancel_token: cancel_token.clone(),
+        });
 
         // Handle cancellation
         if let Some(token) = cancel_token {
@@ -441,6 +500,11 @@ impl RateLimiter for SlidingWindowRateLimiter {
                     result.map_err(|_| RateLimitError::Disposed)?
                 }
                 _ = token.cancelled() => {
+                    // The request may already have been queued; evict it so
+                    // its slot doesn't leak in `queue_count` forever (it's a
+                    // no-op if the request was granted immediately or hasn't
+                    // reached the queue yet).
+                    let _ = self.requests_tx.send(Request::Cancel { id }).await;
                     Err(RateLimitError::Cancelled)
                 }
             }
@@ -456,14 +520,20 @@ impl RateLimiter for SlidingWindowRateLimiter {
         RateLimiterStatistics {
             current_available_permits: Self::available_permits(&s, &self.config) as i64,
             current_queued_count: s.queue_count,
+            current_waiting_count: s.queue.len() as u32,
             total_successful_leases: self.total_successful_leases.load(Ordering::Relaxed) as u64,
             total_failed_leases: self.total_failed_leases.load(Ordering::Relaxed) as u64,
+            queued_lease_count: s.queued_lease_count,
+            total_queue_wait_time: s.total_queue_wait_time,
+            max_queue_wait_time: s.max_queue_wait_time,
+            dropped_permits: 0,
         }
     }
 
     fn idle_duration(&self) -> Option<Duration> {
         let s = self.state.lock().unwrap();
-        s.idle_since.map(|since| since.elapsed())
+        s.idle_since
+            .map(|since| self.config.clock.now().duration_since(since))
     }
 }
 
@@ -503,7 +573,7 @@ impl ReplenishingRateLimiter for SlidingWindowRateLimiter {
 }
 
 /// Configuration options for the sliding window rate limiter
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct SlidingWindowRateLimiterOptions {
     /// Maximum number of permits that can be leased in a window
     pub permit_limit: u32,
@@ -522,6 +592,41 @@ pub struct SlidingWindowRateLimiterOptions {
 
     /// Whether to automatically replenish permits
     pub auto_replenishment: bool,
+
+    /// Maximum time a request may sit in the queue before it is failed with
+    /// a `RetryAfter` hint instead of waiting indefinitely. `None` disables
+    /// the timeout (the default), leaving queued requests to wait until
+    /// permits free up or the caller cancels.
+    pub max_queue_duration: Option<Duration>,
+
+    /// Clock used to timestamp acquisitions and expire segments.
+    ///
+    /// Defaults to [`SystemClock`]. Swap in [`TokioClock`] under a paused
+    /// runtime for deterministic tests, or [`CachedClock`] to trade a bit of
+    /// precision for far fewer timer reads under heavy contention.
+    pub clock: Arc<dyn Clock>,
+
+    /// Capacity of the internal channel used to dispatch requests to the
+    /// background processing task. Once full, `acquire_async` awaits a free
+    /// slot via `Sender::reserve()` before enqueuing, providing backpressure
+    /// instead of buffering requests without bound.
+    pub request_channel_capacity: usize,
+}
+
+impl std::fmt::Debug for SlidingWindowRateLimiterOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SlidingWindowRateLimiterOptions")
+            .field("permit_limit", &self.permit_limit)
+            .field("window", &self.window)
+            .field("segments_per_window", &self.segments_per_window)
+            .field("queue_limit", &self.queue_limit)
+            .field("queue_processing_order", &self.queue_processing_order)
+            .field("auto_replenishment", &self.auto_replenishment)
+            .field("max_queue_duration", &self.max_queue_duration)
+            .field("clock", &"<dyn Clock>")
+            .field("request_channel_capacity", &self.request_channel_capacity)
+            .finish()
+    }
 }
 
 impl Default for SlidingWindowRateLimiterOptions {
@@ -533,6 +638,9 @@ impl Default for SlidingWindowRateLimiterOptions {
             queue_limit: 0,
             queue_processing_order: QueueProcessingOrder::OldestFirst,
             auto_replenishment: true,
+            max_queue_duration: None,
+            clock: Arc::new(SystemClock),
+            request_channel_capacity: 1024,
         }
     }
 }
@@ -544,6 +652,13 @@ struct State {
     idle_since: Option<Instant>,
     auto_replenish_timer: Option<tokio::task::JoinHandle<()>>,
     disposed: bool,
+    /// Count of leases that had to wait in the queue before being granted.
+    queued_lease_count: u64,
+    /// Running total of queue wait time across leases counted by
+    /// `queued_lease_count`.
+    total_queue_wait_time: Duration,
+    /// Longest queue wait time observed so far.
+    max_queue_wait_time: Duration,
 }
 
 struct Segment {
@@ -552,14 +667,15 @@ struct Segment {
 }
 
 struct QueuedRequest {
+    id: u64,
     permits_requested: u32,
     response: oneshot::Sender<Result<RateLimitLease, RateLimitError>>,
-    #[allow(dead_code)]
     queued_at: Instant,
 }
 
 enum Request {
     AcquireAsync {
+        id: u64,
         permit_count: u32,
         response: oneshot::Sender<Result<RateLimitLease, RateLimitError>>,
         _cancel_token: Option<CancellationToken>,
@@ -567,6 +683,11 @@ enum Request {
     TryReplenish {
         response: oneshot::Sender<bool>,
     },
+    /// Evicts the queued request with `id`, reclaiming its `queue_count`
+    /// slot. Sent by `acquire_async` when its `CancellationToken` fires.
+    Cancel {
+        id: u64,
+    },
 }
 
 enum AcquireResult {
@@ -577,6 +698,7 @@ enum AcquireResult {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::TokioClock;
 
     #[tokio::test]
     async fn test_basic_sliding_window() {
@@ -587,6 +709,9 @@ mod tests {
             queue_limit: 0,
             queue_processing_order: QueueProcessingOrder::OldestFirst,
             auto_replenishment: false,
+            max_queue_duration: None,
+            clock: Arc::new(SystemClock),
+            request_channel_capacity: 1024,
         };
 
         let limiter = SlidingWindowRateLimiter::new(options);
@@ -623,6 +748,9 @@ mod tests {
             queue_limit: 0,
             queue_processing_order: QueueProcessingOrder::OldestFirst,
             auto_replenishment: false,
+            max_queue_duration: None,
+            clock: Arc::new(SystemClock),
+            request_channel_capacity: 1024,
         };
 
         let limiter = SlidingWindowRateLimiter::new(options);
@@ -655,6 +783,9 @@ mod tests {
             queue_limit: 10,
             queue_processing_order: QueueProcessingOrder::OldestFirst,
             auto_replenishment: true,
+            max_queue_duration: None,
+            clock: Arc::new(SystemClock),
+            request_channel_capacity: 1024,
         };
 
         let limiter = SlidingWindowRateLimiter::new(options);
@@ -686,4 +817,175 @@ mod tests {
         let stats = limiter.get_statistics();
         assert_eq!(stats.current_queued_count, 0);
     }
+
+    #[tokio::test]
+    async fn test_cancelled_queued_request_frees_its_queue_slot() {
+        let options = SlidingWindowRateLimiterOptions {
+            permit_limit: 1,
+            window: Duration::from_secs(60),
+            segments_per_window: 1,
+            queue_limit: 1,
+            queue_processing_order: QueueProcessingOrder::OldestFirst,
+            auto_replenishment: false,
+            max_queue_duration: None,
+            clock: Arc::new(SystemClock),
+            request_channel_capacity: 1024,
+        };
+
+        let limiter = SlidingWindowRateLimiter::new(options);
+
+        // Exhaust the only permit so the next request must queue.
+        assert!(limiter.attempt_acquire(1).unwrap().is_acquired());
+
+        let cancel_token = CancellationToken::new();
+        let acquire_task = tokio::spawn({
+            let limiter = limiter.clone();
+            let cancel_token = cancel_token.clone();
+            async move { limiter.acquire_async(1, Some(cancel_token)).await }
+        });
+
+        // Give the request time to reach the queue.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(limiter.get_statistics().current_queued_count, 1);
+
+        cancel_token.cancel();
+        let result = acquire_task.await.unwrap();
+        assert!(matches!(result, Err(RateLimitError::Cancelled)));
+
+        // Give the background processor time to handle the Cancel message.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        // The queue slot must be reclaimed, or queue_limit fills permanently.
+        assert_eq!(limiter.get_statistics().current_queued_count, 0);
+
+        // A fresh request should be able to take the freed queue slot
+        // (nothing will replenish this limiter, so just observe it queues
+        // rather than waiting for it to resolve).
+        let second_task = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire_async(1, None).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(limiter.get_statistics().current_queued_count, 1);
+        second_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_max_queue_duration_fails_stale_requests() {
+        let options = SlidingWindowRateLimiterOptions {
+            permit_limit: 1,
+            window: Duration::from_millis(200),
+            segments_per_window: 4, // replenishment timer sweeps every 50ms
+            queue_limit: 1,
+            queue_processing_order: QueueProcessingOrder::OldestFirst,
+            auto_replenishment: true,
+            max_queue_duration: Some(Duration::from_millis(30)),
+            clock: Arc::new(SystemClock),
+            request_channel_capacity: 1024,
+        };
+
+        let limiter = SlidingWindowRateLimiter::new(options);
+
+        // Exhaust the only permit so the next request must queue. The
+        // replenishment timer's periodic sweep should fail the queued
+        // request via max_queue_duration well before the permit's segment
+        // expires on its own.
+        assert!(limiter.attempt_acquire(1).unwrap().is_acquired());
+
+        let result = tokio::time::timeout(
+            Duration::from_millis(500),
+            limiter.acquire_async(1, None),
+        )
+        .await
+        .expect("queued request should fail via max_queue_duration rather than hang")
+        .unwrap();
+
+        assert!(!result.is_acquired());
+        assert!(result.retry_after().is_some());
+        assert_eq!(limiter.get_statistics().current_queued_count, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_tokio_clock_allows_deterministic_segment_expiry() {
+        let options = SlidingWindowRateLimiterOptions {
+            permit_limit: 5,
+            window: Duration::from_millis(100),
+            segments_per_window: 5,
+            queue_limit: 0,
+            queue_processing_order: QueueProcessingOrder::OldestFirst,
+            auto_replenishment: false,
+            max_queue_duration: None,
+            clock: Arc::new(TokioClock),
+            request_channel_capacity: 1024,
+        };
+
+        let limiter = SlidingWindowRateLimiter::new(options);
+
+        assert!(limiter.attempt_acquire(5).unwrap().is_acquired());
+        assert!(!limiter.attempt_acquire(1).unwrap().is_acquired());
+
+        // Advance the virtual clock past the window instead of sleeping in
+        // real time; the paused runtime's clock drives `TokioClock` directly.
+        tokio::time::advance(Duration::from_millis(105)).await;
+
+        let stats = limiter.get_statistics();
+        assert_eq!(stats.current_available_permits, 5);
+    }
+
+    #[tokio::test]
+    async fn test_saturated_request_channel_applies_backpressure() {
+        let clock = crate::utils::ManualClock::new();
+        let options = SlidingWindowRateLimiterOptions {
+            permit_limit: 10,
+            window: Duration::from_secs(60),
+            segments_per_window: 1,
+            queue_limit: 100,
+            queue_processing_order: QueueProcessingOrder::OldestFirst,
+            auto_replenishment: false,
+            max_queue_duration: None,
+            clock: Arc::new(clock.clone()),
+            request_channel_capacity: 1,
+        };
+
+        let limiter = SlidingWindowRateLimiter::new(options);
+
+        // Exhaust the permits so subsequent requests queue behind the
+        // background processor rather than completing instantly.
+        assert!(limiter.attempt_acquire(10).unwrap().is_acquired());
+
+        // With a channel capacity of 1, many concurrent `acquire_async`
+        // callers must await a reserved slot rather than piling up
+        // unboundedly in the dispatcher's channel; they should all still
+        // eventually be admitted (and queue) once slots free up.
+        let mut tasks = Vec::new();
+        for _ in 0..10 {
+            let limiter = limiter.clone();
+            tasks.push(tokio::spawn(
+                async move { limiter.acquire_async(1, None).await },
+            ));
+        }
+
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while limiter.get_statistics().current_queued_count < 10 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("reserve-based send should not hang");
+        assert_eq!(limiter.get_statistics().current_queued_count, 10);
+
+        // Nothing grants a queued request until the exhausting segment
+        // expires; advance past the window so the queue drains and every
+        // caller's acquire_async resolves.
+        clock.advance(Duration::from_secs(60));
+        assert!(limiter.try_replenish());
+
+        for task in tasks {
+            let result = tokio::time::timeout(Duration::from_secs(1), task)
+                .await
+                .expect("queued request should resolve once the window expires")
+                .unwrap();
+            assert!(result.is_ok());
+        }
+    }
 }
\ No newline at end of file
@@ -4,20 +4,67 @@
 //! Tokens are added to the bucket at a fixed rate and consumed when permits
 //! are acquired. Unlike concurrency limiters, tokens are not returned when
 //! leases are dropped.
+//!
+//! `TokenBucketRateLimiter` implements both `RateLimiter` and
+//! `ReplenishingRateLimiter`: callers that don't need the replenishment
+//! controls can use it as a plain `RateLimiter`, while callers that want to
+//! drive refills themselves (`try_replenish()`) or inspect the
+//! configuration (`is_auto_replenishing()`, `replenishment_period()`) can
+//! reach for the latter.
+//!
+//! Auto-replenishing buckets also lazily top up `available_tokens` for
+//! elapsed time on every `attempt_acquire`/`acquire_async` call, so the
+//! bucket tracks wall-clock time continuously rather than only catching up
+//! on `run_replenishment_timer` ticks; spawning that timer is still useful
+//! to drain queued waiters promptly when no new requests arrive.
+//!
+//! `TokenBucketRateLimiterOptions::one_time_burst` layers an extra, one-shot
+//! pool of permits on top of `token_limit` for absorbing a cold-start spike;
+//! it's drawn before the regular bucket and is never restored by
+//! replenishment.
+//!
+//! `TokenBucketRateLimiter::update()` lets `token_limit`, `tokens_per_period`,
+//! and `replenishment_period` be changed live, without recreating the
+//! limiter or dropping outstanding leases - useful for dynamic throttling
+//! policies that respond to changing load.
+//!
+//! `TokenBucketRateLimiterOptions::clock` lets tests swap in a
+//! [`ManualClock`](crate::utils::ManualClock) to drive replenishment and idle
+//! tracking deterministically instead of sleeping in real wall-clock time.
+//!
+//! `TokenBucketRateLimiter::ready_notifier()` wakes callers blocked on an
+//! exhausted bucket as soon as `available_tokens` crosses back up to
+//! `TokenBucketRateLimiterOptions::ready_threshold`, instead of making them
+//! poll `attempt_acquire` or hold a queue slot.
+//!
+//! This type enforces a single budget. For the "N requests/s AND M MB/s"
+//! case - two independent budgets (e.g. bandwidth and operation rate) that
+//! must both have room before a request proceeds, the way Firecracker/
+//! cloud-hypervisor throttle block devices - see `MultiTokenBucketLimiter`
+//! instead of composing two of these and reconciling their queues by hand.
+//!
+//! `TokenBucketRateLimiterOptions::preconfig_burst()` and
+//! `::preconfig_throughput()` derive `token_limit`, `tokens_per_period`, and
+//! `replenishment_period` from a target rate and a window, encoding the
+//! non-obvious math (as Riven does against Riot's API) of staying just under
+//! a remote's windowed rate limit - the former front-loads a burst, the
+//! latter spreads consumption evenly across the window - instead of
+//! requiring every caller to work it out by hand.
 
 use crate::core::traits::{RateLimiter, ReplenishingRateLimiter};
 use crate::core::{QueueProcessingOrder, RateLimitError, RateLimitLease, RateLimiterStatistics};
+use crate::utils::{Clock, SystemClock};
 use async_trait::async_trait;
 use parking_lot::Mutex;
 use std::collections::VecDeque;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::oneshot;
+use tokio::sync::{oneshot, Notify};
 use tokio_util::sync::CancellationToken;
 
 /// Options for configuring a `TokenBucketRateLimiter`.
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct TokenBucketRateLimiterOptions {
     /// Maximum number of tokens that can be stored in the bucket.
     pub token_limit: u32,
@@ -37,6 +84,73 @@ pub struct TokenBucketRateLimiterOptions {
     /// If true, tokens are automatically replenished on a timer.
     /// If false, `try_replenish()` must be called manually.
     pub auto_replenishment: bool,
+
+    /// Extra permits available once, on top of `token_limit`, layered in
+    /// "ahead of" the regular bucket so a cold-start burst of traffic can be
+    /// absorbed without raising steady-state throughput. Drawn before the
+    /// regular bucket and never refilled by `try_replenish()` or the
+    /// replenishment timer - once exhausted, it stays at zero.
+    pub one_time_burst: u32,
+
+    /// If true, manual `try_replenish()` calls add tokens proportional to the
+    /// wall-clock time elapsed since the last replenishment (Firecracker's
+    /// `complete_refill_time` approach) instead of always adding a flat
+    /// `tokens_per_period`. Any fractional remainder that doesn't amount to a
+    /// whole token carries forward to the next call rather than being
+    /// dropped. Auto-replenishing buckets already replenish proportionally
+    /// to elapsed time and are unaffected by this flag.
+    pub replenish_fractionally: bool,
+
+    /// Clock used to time replenishment and idle tracking.
+    ///
+    /// Defaults to [`SystemClock`]. `new()` cannot take this as a positional
+    /// parameter without breaking every existing call site, so override it
+    /// afterwards with struct-update syntax, e.g. swapping in a
+    /// [`ManualClock`](crate::utils::ManualClock) for deterministic tests:
+    /// `TokenBucketRateLimiterOptions { clock: Arc::new(clock), ..options }`.
+    pub clock: Arc<dyn Clock>,
+
+    /// Regular-bucket level (excluding the one-time burst) that
+    /// `TokenBucketRateLimiter::ready_notifier()` waiters are woken at, once
+    /// `available_tokens` rises from below this threshold to at-or-above it.
+    /// Defaults to `1` (any capacity at all), following Firecracker's
+    /// blocked-caller-registers-and-retries contract. Override with
+    /// struct-update syntax, same as `clock`.
+    pub ready_threshold: u32,
+}
+
+impl std::fmt::Debug for TokenBucketRateLimiterOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenBucketRateLimiterOptions")
+            .field("token_limit", &self.token_limit)
+            .field("tokens_per_period", &self.tokens_per_period)
+            .field("replenishment_period", &self.replenishment_period)
+            .field("queue_limit", &self.queue_limit)
+            .field("queue_processing_order", &self.queue_processing_order)
+            .field("auto_replenishment", &self.auto_replenishment)
+            .field("one_time_burst", &self.one_time_burst)
+            .field("replenish_fractionally", &self.replenish_fractionally)
+            .field("clock", &"<dyn Clock>")
+            .field("ready_threshold", &self.ready_threshold)
+            .finish()
+    }
+}
+
+/// Live-tunable subset of `TokenBucketRateLimiterOptions`, passed to
+/// `TokenBucketRateLimiter::update()` to change bucket sizing or refill rate
+/// without recreating the limiter (cloud-hypervisor's shared `RateLimiter`
+/// supports the same kind of live reconfiguration). A field left `None`
+/// keeps its current value.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BucketUpdate {
+    /// New maximum number of tokens the bucket can hold.
+    pub token_limit: Option<u32>,
+
+    /// New number of tokens added to the bucket each replenishment period.
+    pub tokens_per_period: Option<u32>,
+
+    /// New interval between replenishments.
+    pub replenishment_period: Option<Duration>,
 }
 
 impl TokenBucketRateLimiterOptions {
@@ -45,7 +159,7 @@ impl TokenBucketRateLimiterOptions {
     /// # Errors
     ///
     /// Returns an error if:
-    /// - `token_limit` is 0
+    /// - `token_limit` and `one_time_burst` are both 0
     /// - `tokens_per_period` is 0
     /// - `replenishment_period` is zero
     pub fn new(
@@ -55,10 +169,12 @@ impl TokenBucketRateLimiterOptions {
         queue_limit: u32,
         queue_processing_order: QueueProcessingOrder,
         auto_replenishment: bool,
+        one_time_burst: u32,
+        replenish_fractionally: bool,
     ) -> Result<Self, RateLimitError> {
-        if token_limit == 0 {
+        if token_limit == 0 && one_time_burst == 0 {
             return Err(RateLimitError::InvalidParameter(
-                "token_limit must be greater than 0".to_string(),
+                "token_limit and one_time_burst cannot both be 0".to_string(),
             ));
         }
 
@@ -81,8 +197,138 @@ impl TokenBucketRateLimiterOptions {
             queue_limit,
             queue_processing_order,
             auto_replenishment,
+            one_time_burst,
+            replenish_fractionally,
+            clock: Arc::new(SystemClock),
+            ready_threshold: 1,
         })
     }
+
+    /// Burst profile's fraction of one window's worth of tokens that sits in
+    /// the bucket and can be spent all at once - close to `1.0` so a caller
+    /// can drain nearly the whole window's budget in one go.
+    const BURST_PROFILE_BURST_PCT: f64 = 0.9;
+
+    /// Burst profile's slack added on top of `window` for
+    /// `replenishment_period`, matching Riven's ~1s overhead when trailing
+    /// an upstream API's own window boundary.
+    const BURST_PROFILE_OVERHEAD: Duration = Duration::from_secs(1);
+
+    /// Throughput profile's fraction of one window's worth of tokens that
+    /// sits in the bucket - small, so consumption can't be front-loaded and
+    /// is instead spread evenly across the window.
+    const THROUGHPUT_PROFILE_BURST_PCT: f64 = 0.1;
+
+    /// Throughput profile's slack added on top of `window` for
+    /// `replenishment_period` - small, since there's no burst headroom to
+    /// protect against racing the remote's window boundary.
+    const THROUGHPUT_PROFILE_OVERHEAD: Duration = Duration::from_millis(50);
+
+    /// Derive options that maximize how fast a caller can drain the bucket
+    /// while still staying under `target_rate` permits per `window`, the way
+    /// Riven front-loads a burst against Riot's API just under its windowed
+    /// limit: 90% of the window's budget is held in the bucket and spendable
+    /// at once, with a generous (~1s) overhead added to
+    /// `replenishment_period` so refills trail the remote's own window
+    /// boundary rather than racing it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as [`Self::new`], plus if
+    /// `target_rate` is not positive or `window` is zero.
+    pub fn preconfig_burst(
+        target_rate: f64,
+        window: Duration,
+        queue_limit: u32,
+        queue_processing_order: QueueProcessingOrder,
+        auto_replenishment: bool,
+        replenish_fractionally: bool,
+    ) -> Result<Self, RateLimitError> {
+        Self::from_target_rate(
+            target_rate,
+            window,
+            Self::BURST_PROFILE_BURST_PCT,
+            Self::BURST_PROFILE_OVERHEAD,
+            queue_limit,
+            queue_processing_order,
+            auto_replenishment,
+            replenish_fractionally,
+        )
+    }
+
+    /// Derive options that spread consumption evenly across `window` rather
+    /// than allowing it to be front-loaded, while still staying under
+    /// `target_rate` permits per `window`: only a small fraction of the
+    /// window's budget is held in the bucket, with a small overhead added to
+    /// `replenishment_period`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::preconfig_burst`].
+    pub fn preconfig_throughput(
+        target_rate: f64,
+        window: Duration,
+        queue_limit: u32,
+        queue_processing_order: QueueProcessingOrder,
+        auto_replenishment: bool,
+        replenish_fractionally: bool,
+    ) -> Result<Self, RateLimitError> {
+        Self::from_target_rate(
+            target_rate,
+            window,
+            Self::THROUGHPUT_PROFILE_BURST_PCT,
+            Self::THROUGHPUT_PROFILE_OVERHEAD,
+            queue_limit,
+            queue_processing_order,
+            auto_replenishment,
+            replenish_fractionally,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn from_target_rate(
+        target_rate: f64,
+        window: Duration,
+        burst_pct: f64,
+        duration_overhead: Duration,
+        queue_limit: u32,
+        queue_processing_order: QueueProcessingOrder,
+        auto_replenishment: bool,
+        replenish_fractionally: bool,
+    ) -> Result<Self, RateLimitError> {
+        if !(target_rate.is_finite() && target_rate > 0.0) {
+            return Err(RateLimitError::InvalidParameter(
+                "target_rate must be a positive, finite number".to_string(),
+            ));
+        }
+
+        if window.is_zero() {
+            return Err(RateLimitError::InvalidParameter(
+                "window must be greater than zero".to_string(),
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&burst_pct) {
+            return Err(RateLimitError::InvalidParameter(
+                "burst_pct must be between 0.0 and 1.0".to_string(),
+            ));
+        }
+
+        let tokens_per_period = (target_rate * window.as_secs_f64()).round() as u32;
+        let token_limit = (tokens_per_period as f64 * burst_pct).round() as u32;
+        let replenishment_period = window + duration_overhead;
+
+        Self::new(
+            token_limit,
+            tokens_per_period,
+            replenishment_period,
+            queue_limit,
+            queue_processing_order,
+            auto_replenishment,
+            0,
+            replenish_fractionally,
+        )
+    }
 }
 
 /// Internal state for the token bucket limiter.
@@ -90,6 +336,10 @@ struct State {
     /// Number of tokens currently available (fractional for fill rate).
     available_tokens: f64,
 
+    /// Remaining one-time burst permits, drawn before `available_tokens` and
+    /// never replenished.
+    burst_remaining: u32,
+
     /// Queue of pending requests waiting for tokens.
     queue: VecDeque<QueuedRequest>,
 
@@ -104,6 +354,20 @@ struct State {
 
     /// Whether the limiter has been disposed.
     disposed: bool,
+
+    /// Maximum number of tokens that can be stored in the bucket. Lives
+    /// behind the same lock as `available_tokens` (rather than in the
+    /// immutable `config`) so `TokenBucketRateLimiter::update()` can change
+    /// it live without racing replenishment or acquisition.
+    token_limit: u32,
+
+    /// Number of tokens added to the bucket each replenishment period.
+    /// Live-tunable via `update()`, same rationale as `token_limit`.
+    tokens_per_period: u32,
+
+    /// How frequently tokens are added to the bucket. Live-tunable via
+    /// `update()`, same rationale as `token_limit`.
+    replenishment_period: Duration,
 }
 
 /// A queued request waiting for tokens.
@@ -113,6 +377,10 @@ struct QueuedRequest {
 
     /// Channel to send the result when tokens become available.
     response: oneshot::Sender<Result<RateLimitLease, RateLimitError>>,
+
+    /// Time the request was pushed onto the queue, used to compute queue
+    /// wait-time statistics once the request is granted.
+    queued_at: Instant,
 }
 
 /// A rate limiter that uses the token bucket algorithm.
@@ -133,8 +401,27 @@ pub struct TokenBucketRateLimiter {
     /// Counter for failed lease acquisitions.
     failed_leases: Arc<AtomicU64>,
 
+    /// Count of leases that had to wait in the queue before being granted.
+    queued_lease_count: Arc<AtomicU64>,
+
+    /// Running total of queue wait time (in nanoseconds) across leases
+    /// counted by `queued_lease_count`.
+    total_queue_wait_nanos: Arc<AtomicU64>,
+
+    /// Longest queue wait time (in nanoseconds) observed so far.
+    max_queue_wait_nanos: Arc<AtomicU64>,
+
+    /// Total replenishment tokens discarded because the bucket was already
+    /// at `token_limit` when a replenishment ran.
+    dropped_permits: Arc<AtomicU64>,
+
     /// Cancellation token for stopping the replenishment timer.
     replenishment_cancel: CancellationToken,
+
+    /// Fired whenever `available_tokens` rises from below
+    /// `config.ready_threshold` to at-or-above it, so callers blocked on
+    /// `ready_notifier()` wake up and retry instead of polling.
+    ready: Notify,
 }
 
 impl TokenBucketRateLimiter {
@@ -145,9 +432,9 @@ impl TokenBucketRateLimiter {
     /// Returns an error if the options are invalid.
     pub fn new(options: TokenBucketRateLimiterOptions) -> Result<Self, RateLimitError> {
         // Validate options
-        if options.token_limit == 0 {
+        if options.token_limit == 0 && options.one_time_burst == 0 {
             return Err(RateLimitError::InvalidParameter(
-                "token_limit must be greater than 0".to_string(),
+                "token_limit and one_time_burst cannot both be 0".to_string(),
             ));
         }
 
@@ -163,14 +450,18 @@ impl TokenBucketRateLimiter {
             ));
         }
 
-        let now = Instant::now();
+        let now = options.clock.now();
         let state = State {
             available_tokens: options.token_limit as f64,
+            burst_remaining: options.one_time_burst,
             queue: VecDeque::new(),
             queue_count: 0,
             last_replenishment: now,
             idle_since: Some(now),
             disposed: false,
+            token_limit: options.token_limit,
+            tokens_per_period: options.tokens_per_period,
+            replenishment_period: options.replenishment_period,
         };
 
         Ok(Self {
@@ -178,7 +469,12 @@ impl TokenBucketRateLimiter {
             config: options,
             successful_leases: Arc::new(AtomicU64::new(0)),
             failed_leases: Arc::new(AtomicU64::new(0)),
+            queued_lease_count: Arc::new(AtomicU64::new(0)),
+            total_queue_wait_nanos: Arc::new(AtomicU64::new(0)),
+            max_queue_wait_nanos: Arc::new(AtomicU64::new(0)),
+            dropped_permits: Arc::new(AtomicU64::new(0)),
             replenishment_cancel: CancellationToken::new(),
+            ready: Notify::new(),
         })
     }
 
@@ -186,13 +482,19 @@ impl TokenBucketRateLimiter {
     ///
     /// This method should be spawned as a background task when auto-replenishment
     /// is enabled. It periodically adds tokens to the bucket.
+    ///
+    /// The sleep duration is re-read from `State` on every iteration (rather
+    /// than fixed once via `tokio::time::interval`), so a `replenishment_period`
+    /// change made through `update()` takes effect on the very next tick
+    /// instead of requiring the timer to be respawned.
     pub async fn run_replenishment_timer(&self) {
-        let mut interval = tokio::time::interval(self.config.replenishment_period);
         let cancel = self.replenishment_cancel.clone();
 
         loop {
+            let period = self.state.lock().replenishment_period;
+
             tokio::select! {
-                _ = interval.tick() => {
+                _ = tokio::time::sleep(period) => {
                     self.replenish();
                 }
                 _ = cancel.cancelled() => {
@@ -205,37 +507,111 @@ impl TokenBucketRateLimiter {
     /// Replenish tokens in the bucket.
     fn replenish(&self) {
         let mut state = self.state.lock();
+        self.replenish_locked(&mut state);
+    }
 
+    /// Shared replenishment math, operating on an already-locked `State`.
+    ///
+    /// Used both by the periodic `run_replenishment_timer` task and by
+    /// `lazy_replenish_locked`, which calls this inline on every acquire so
+    /// that auto-replenishing buckets stay accurate between timer ticks (or
+    /// even if the timer was never spawned at all).
+    fn replenish_locked(&self, state: &mut State) {
         if state.disposed {
             return;
         }
 
-        let now = Instant::now();
-        let tokens_to_add = if self.config.auto_replenishment {
+        let now = self.config.clock.now();
+        let before = state.available_tokens;
+
+        if self.config.auto_replenishment {
             // Auto-replenishment: Calculate based on elapsed time
             let elapsed = now.duration_since(state.last_replenishment);
-            let periods = elapsed.as_secs_f64() / self.config.replenishment_period.as_secs_f64();
-            periods * self.config.tokens_per_period as f64
-        } else {
-            // Manual replenishment: Always add fixed tokens_per_period
-            self.config.tokens_per_period as f64
-        };
+            let periods = elapsed.as_secs_f64() / state.replenishment_period.as_secs_f64();
+            let tokens_to_add = periods * state.tokens_per_period as f64;
+
+            if tokens_to_add > 0.0 {
+                self.record_overflow(state, tokens_to_add);
+                state.available_tokens =
+                    (state.available_tokens + tokens_to_add).min(state.token_limit as f64);
+                state.last_replenishment = now;
+                self.update_idle_tracking(state, now);
+                self.process_queue_internal(state);
+            }
+        } else if self.config.replenish_fractionally {
+            // Manual, fractional replenishment: add tokens proportional to
+            // elapsed wall-clock time, carrying forward any remainder that
+            // doesn't amount to a whole token.
+            if state.available_tokens >= state.token_limit as f64 {
+                // Already full - advance the clock without accruing a
+                // remainder that would otherwise accumulate indefinitely.
+                state.last_replenishment = now;
+                return;
+            }
 
-        if tokens_to_add > 0.0 {
-            // Add tokens (up to limit)
-            state.available_tokens = (state.available_tokens + tokens_to_add)
-                .min(self.config.token_limit as f64);
+            let elapsed = now.duration_since(state.last_replenishment);
+            let periods = elapsed.as_secs_f64() / state.replenishment_period.as_secs_f64();
+            let tokens_to_add = (periods * state.tokens_per_period as f64).floor();
+
+            if tokens_to_add > 0.0 {
+                self.record_overflow(state, tokens_to_add);
+                state.available_tokens =
+                    (state.available_tokens + tokens_to_add).min(state.token_limit as f64);
+                let period_per_token = state.replenishment_period / state.tokens_per_period;
+                state.last_replenishment += period_per_token * tokens_to_add as u32;
+                self.update_idle_tracking(state, now);
+                self.process_queue_internal(state);
+            }
+        } else {
+            // Manual, fixed replenishment: always add tokens_per_period.
+            self.record_overflow(state, state.tokens_per_period as f64);
+            state.available_tokens =
+                (state.available_tokens + state.tokens_per_period as f64).min(state.token_limit as f64);
             state.last_replenishment = now;
+            self.update_idle_tracking(state, now);
+            self.process_queue_internal(state);
+        }
 
-            // Update idle tracking
-            if state.available_tokens >= self.config.token_limit as f64 - 0.001
-                && state.idle_since.is_none()
-            {
-                state.idle_since = Some(now);
-            }
+        self.maybe_notify_ready(before, state.available_tokens);
+    }
 
-            // Process queue
-            self.process_queue_internal(&mut state);
+    /// Wakes `ready_notifier()` waiters if `available_tokens` just rose from
+    /// below `config.ready_threshold` to at-or-above it.
+    fn maybe_notify_ready(&self, before: f64, after: f64) {
+        let threshold = self.config.ready_threshold as f64;
+        if before < threshold && after >= threshold {
+            self.ready.notify_waiters();
+        }
+    }
+
+    /// Records how many of `tokens_to_add` would overflow `token_limit`
+    /// given `state.available_tokens` before the add, as permits discarded
+    /// rather than silently clamped away.
+    fn record_overflow(&self, state: &State, tokens_to_add: f64) {
+        let overflow = (state.available_tokens + tokens_to_add) - state.token_limit as f64;
+        if overflow > 0.0 {
+            self.dropped_permits
+                .fetch_add(overflow as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Marks the bucket as having become idle (full) at `now`, if it just
+    /// reached capacity and wasn't already tracked as idle.
+    fn update_idle_tracking(&self, state: &mut State, now: Instant) {
+        if state.available_tokens >= state.token_limit as f64 - 0.001 && state.idle_since.is_none()
+        {
+            state.idle_since = Some(now);
+        }
+    }
+
+    /// Lazily top up `available_tokens` for elapsed time before checking or
+    /// consuming them, so an auto-replenishing bucket stays accurate between
+    /// `run_replenishment_timer` ticks without callers needing to wait for
+    /// the next tick. No-op for manually replenished buckets, which only
+    /// gain tokens via an explicit `try_replenish()`.
+    fn lazy_replenish_locked(&self, state: &mut State) {
+        if self.config.auto_replenishment {
+            self.replenish_locked(state);
         }
     }
 
@@ -266,15 +642,16 @@ impl TokenBucketRateLimiter {
             }
 
             // Check if we have enough tokens
-            if state.available_tokens >= next_req.token_count as f64 {
+            if Self::total_available(state) >= next_req.token_count as f64 {
                 let req = match self.config.queue_processing_order {
                     QueueProcessingOrder::OldestFirst => state.queue.pop_front(),
                     QueueProcessingOrder::NewestFirst => state.queue.pop_back(),
                 }.unwrap();
 
-                state.available_tokens -= req.token_count as f64;
+                Self::draw_tokens(state, req.token_count);
                 state.queue_count -= req.token_count;
                 state.idle_since = None;
+                self.record_queue_wait(self.config.clock.now().duration_since(req.queued_at));
 
                 // Send lease (no cleanup needed - tokens don't return)
                 let lease = self.create_lease(req.token_count);
@@ -287,6 +664,21 @@ impl TokenBucketRateLimiter {
         }
     }
 
+    /// Total permits currently available across the one-time burst and the
+    /// regular bucket.
+    fn total_available(state: &State) -> f64 {
+        state.burst_remaining as f64 + state.available_tokens
+    }
+
+    /// Draws `token_count` permits, consuming the one-time burst first and
+    /// falling back to the regular bucket. Callers must have already
+    /// confirmed `total_available(state) >= token_count as f64`.
+    fn draw_tokens(state: &mut State, token_count: u32) {
+        let from_burst = token_count.min(state.burst_remaining);
+        state.burst_remaining -= from_burst;
+        state.available_tokens -= (token_count - from_burst) as f64;
+    }
+
     /// Create a lease (no cleanup needed for token bucket).
     fn create_lease(&self, token_count: u32) -> RateLimitLease {
         if token_count == 0 {
@@ -299,17 +691,18 @@ impl TokenBucketRateLimiter {
 
     /// Calculate retry-after duration for failed requests.
     fn calculate_retry_after(&self, state: &State, token_count: u32) -> Duration {
-        // Calculate how many tokens we need beyond what's available
-        let tokens_needed = (token_count as f64 - state.available_tokens).max(0.0);
+        // Calculate how many tokens we need beyond what's available (the
+        // one-time burst, once exhausted, never contributes here again)
+        let tokens_needed = (token_count as f64 - Self::total_available(state)).max(0.0);
 
         // Account for queued tokens
         let total_tokens_needed = tokens_needed + state.queue_count as f64;
 
         // Calculate how many periods we need to wait
-        let periods_needed = (total_tokens_needed / self.config.tokens_per_period as f64).ceil() as u32;
+        let periods_needed = (total_tokens_needed / state.tokens_per_period as f64).ceil() as u32;
         let periods = periods_needed.max(1);
 
-        self.config.replenishment_period * periods
+        state.replenishment_period * periods
     }
 
     /// Try to acquire tokens immediately.
@@ -319,18 +712,18 @@ impl TokenBucketRateLimiter {
         }
 
         // Special case: token_count == 0 for state checking
-        if token_count == 0 && state.available_tokens > 0.0 {
+        if token_count == 0 && Self::total_available(state) > 0.0 {
             self.successful_leases.fetch_add(1, Ordering::Relaxed);
             return Some(RateLimitLease::success());
         }
 
         // Check if we have enough tokens and can acquire immediately
-        if state.available_tokens >= token_count as f64 && token_count > 0 {
+        if Self::total_available(state) >= token_count as f64 && token_count > 0 {
             // Can acquire if no queue or if we process newest first
             if state.queue.is_empty()
                 || self.config.queue_processing_order == QueueProcessingOrder::NewestFirst
             {
-                state.available_tokens -= token_count as f64;
+                Self::draw_tokens(state, token_count);
                 state.idle_since = None;
                 self.successful_leases.fetch_add(1, Ordering::Relaxed);
 
@@ -341,6 +734,165 @@ impl TokenBucketRateLimiter {
 
         None
     }
+
+    /// Record that a queued lease waited `wait` before being granted.
+    fn record_queue_wait(&self, wait: Duration) {
+        let nanos = wait.as_nanos().min(u128::from(u64::MAX)) as u64;
+        self.queued_lease_count.fetch_add(1, Ordering::Relaxed);
+        self.total_queue_wait_nanos.fetch_add(nanos, Ordering::Relaxed);
+        self.max_queue_wait_nanos.fetch_max(nanos, Ordering::Relaxed);
+    }
+
+    /// The current maximum number of tokens the bucket can hold. Reflects
+    /// any live changes made via `update()`.
+    pub fn token_limit(&self) -> u32 {
+        self.state.lock().token_limit
+    }
+
+    /// The current number of tokens added to the bucket each replenishment
+    /// period. Reflects any live changes made via `update()`.
+    pub fn tokens_per_period(&self) -> u32 {
+        self.state.lock().tokens_per_period
+    }
+
+    /// The one-time burst permits remaining. Starts at
+    /// `TokenBucketRateLimiterOptions::one_time_burst` and only ever
+    /// decreases - once drawn down, this never refills.
+    pub fn one_time_burst_remaining(&self) -> u32 {
+        self.state.lock().burst_remaining
+    }
+
+    /// The number of tokens currently available in the regular (non-burst)
+    /// bucket. Unlike `get_statistics().current_available_permits`, this
+    /// doesn't include the one-time burst and isn't truncated to an integer,
+    /// so it reflects partially-replenished fractional state exactly.
+    pub fn available_tokens(&self) -> f64 {
+        self.state.lock().available_tokens
+    }
+
+    /// Forcibly sets the regular bucket's available token count, for tests
+    /// and admission-control code that need to drain or pre-fill the bucket
+    /// directly rather than waiting on replenishment. Errors if `amount`
+    /// exceeds `token_limit`; the one-time burst is untouched. Raising the
+    /// level wakes any queued requests that can now be served.
+    pub fn set_available_tokens(&self, amount: f64) -> Result<(), RateLimitError> {
+        let mut state = self.state.lock();
+        if amount > state.token_limit as f64 {
+            return Err(RateLimitError::InvalidParameter(format!(
+                "amount {} exceeds token_limit {}",
+                amount, state.token_limit
+            )));
+        }
+
+        let before = state.available_tokens;
+        let raising = amount > before;
+        state.available_tokens = amount;
+        if raising {
+            self.process_queue_internal(&mut state);
+        }
+        self.maybe_notify_ready(before, amount);
+        Ok(())
+    }
+
+    /// Returns a `Notify` handle that fires whenever `available_tokens` rises
+    /// from below `TokenBucketRateLimiterOptions::ready_threshold` to
+    /// at-or-above it, letting a caller blocked on an exhausted bucket wait
+    /// for capacity instead of polling via repeated `attempt_acquire` calls
+    /// or holding a queue slot. Mirrors Firecracker's blocked-caller
+    /// registers-and-retries contract: call `notified()` *before*
+    /// re-checking `attempt_acquire`/`available_tokens` to avoid missing a
+    /// notification that fires in between.
+    pub fn ready_notifier(&self) -> &Notify {
+        &self.ready
+    }
+
+    /// Change `token_limit`, `tokens_per_period`, and/or `replenishment_period`
+    /// live, without recreating the limiter or dropping outstanding leases.
+    /// Fields left as `None` in `update` keep their current value.
+    ///
+    /// A smaller `token_limit` clamps `current_available_permits` down
+    /// immediately. `run_replenishment_timer`'s sleep picks up a changed
+    /// `replenishment_period` on its next iteration. Queued requests are
+    /// re-evaluated against the new configuration: some may now be granted,
+    /// and any that now exceed the new total capacity fail immediately with
+    /// `PermitCountExceeded` rather than waiting on a request that can never
+    /// be satisfied.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RateLimitError::InvalidParameter` (mirroring
+    /// `TokenBucketRateLimiterOptions::new`'s validation) if the resulting
+    /// `token_limit`/`tokens_per_period`/`replenishment_period` would be
+    /// invalid. The bucket is left unchanged in that case.
+    pub fn update(&self, update: BucketUpdate) -> Result<(), RateLimitError> {
+        let mut state = self.state.lock();
+
+        let token_limit = update.token_limit.unwrap_or(state.token_limit);
+        let tokens_per_period = update.tokens_per_period.unwrap_or(state.tokens_per_period);
+        let replenishment_period = update
+            .replenishment_period
+            .unwrap_or(state.replenishment_period);
+
+        if token_limit == 0 && self.config.one_time_burst == 0 {
+            return Err(RateLimitError::InvalidParameter(
+                "token_limit and one_time_burst cannot both be 0".to_string(),
+            ));
+        }
+
+        if tokens_per_period == 0 {
+            return Err(RateLimitError::InvalidParameter(
+                "tokens_per_period must be greater than 0".to_string(),
+            ));
+        }
+
+        if replenishment_period.is_zero() {
+            return Err(RateLimitError::InvalidParameter(
+                "replenishment_period must be greater than zero".to_string(),
+            ));
+        }
+
+        state.token_limit = token_limit;
+        state.tokens_per_period = tokens_per_period;
+        state.replenishment_period = replenishment_period;
+
+        // A smaller limit may leave the bucket holding a stale surplus -
+        // clamp it down rather than letting it coast above the new cap.
+        if state.available_tokens > token_limit as f64 {
+            state.available_tokens = token_limit as f64;
+        }
+        let now = self.config.clock.now();
+        self.update_idle_tracking(&mut state, now);
+
+        // Re-evaluate the queue: a request that now exceeds total capacity
+        // outright can never be satisfied and must fail rather than wait
+        // forever; everything else stays queued in its original order for
+        // `process_queue_internal` to grant as tokens allow.
+        let capacity = token_limit + self.config.one_time_burst;
+        let mut still_queued = VecDeque::with_capacity(state.queue.len());
+        while let Some(req) = state.queue.pop_front() {
+            if req.response.is_closed() {
+                state.queue_count -= req.token_count;
+                continue;
+            }
+
+            if req.token_count > capacity {
+                state.queue_count -= req.token_count;
+                let _ = req.response.send(Err(RateLimitError::PermitCountExceeded(
+                    req.token_count,
+                    capacity,
+                )));
+                self.failed_leases.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+
+            still_queued.push_back(req);
+        }
+        state.queue = still_queued;
+
+        self.process_queue_internal(&mut state);
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -349,23 +901,28 @@ impl RateLimiter for TokenBucketRateLimiter {
         // Tokens are called permits in the trait interface
         let token_count = permit_count;
 
-        // Check if request exceeds capacity
-        if token_count > self.config.token_limit {
-            return Err(RateLimitError::PermitCountExceeded(
-                token_count,
-                self.config.token_limit,
-            ));
-        }
-
         let mut state = self.state.lock();
 
         if state.disposed {
             return Err(RateLimitError::Disposed);
         }
 
+        // Check if request exceeds capacity (the most this limiter could
+        // ever grant in one lease: the regular bucket plus a still-intact
+        // one-time burst). `token_limit` is read from `state` since
+        // `update()` can change it live.
+        if token_count > state.token_limit + self.config.one_time_burst {
+            return Err(RateLimitError::PermitCountExceeded(
+                token_count,
+                state.token_limit + self.config.one_time_burst,
+            ));
+        }
+
+        self.lazy_replenish_locked(&mut state);
+
         // Special case: token_count == 0 for checking limiter state
         if token_count == 0 {
-            if state.available_tokens > 0.0 {
+            if Self::total_available(&state) > 0.0 {
                 self.successful_leases.fetch_add(1, Ordering::Relaxed);
                 return Ok(RateLimitLease::success());
             } else {
@@ -394,14 +951,6 @@ impl RateLimiter for TokenBucketRateLimiter {
         // Tokens are called permits in the trait interface
         let token_count = permit_count;
 
-        // Check if request exceeds capacity
-        if token_count > self.config.token_limit {
-            return Err(RateLimitError::PermitCountExceeded(
-                token_count,
-                self.config.token_limit,
-            ));
-        }
-
         // Try immediate acquisition first
         let rx = {
             let mut state = self.state.lock();
@@ -410,6 +959,19 @@ impl RateLimiter for TokenBucketRateLimiter {
                 return Err(RateLimitError::Disposed);
             }
 
+            // Check if request exceeds capacity (the most this limiter could
+            // ever grant in one lease: the regular bucket plus a still-intact
+            // one-time burst). `token_limit` is read from `state` since
+            // `update()` can change it live.
+            if token_count > state.token_limit + self.config.one_time_burst {
+                return Err(RateLimitError::PermitCountExceeded(
+                    token_count,
+                    state.token_limit + self.config.one_time_burst,
+                ));
+            }
+
+            self.lazy_replenish_locked(&mut state);
+
             // Try to acquire immediately
             if let Some(lease) = self.try_acquire_immediate(&mut state, token_count) {
                 return Ok(lease);
@@ -447,6 +1009,7 @@ impl RateLimiter for TokenBucketRateLimiter {
             let request = QueuedRequest {
                 token_count,
                 response: tx,
+                queued_at: self.config.clock.now(),
             };
 
             state.queue.push_back(request);
@@ -499,16 +1062,26 @@ impl RateLimiter for TokenBucketRateLimiter {
         let state = self.state.lock();
 
         RateLimiterStatistics {
-            current_available_permits: state.available_tokens as i64,
+            current_available_permits: Self::total_available(&state) as i64,
             current_queued_count: state.queue_count,
+            current_waiting_count: state.queue.len() as u32,
             total_successful_leases: self.successful_leases.load(Ordering::Relaxed),
             total_failed_leases: self.failed_leases.load(Ordering::Relaxed),
+            queued_lease_count: self.queued_lease_count.load(Ordering::Relaxed),
+            total_queue_wait_time: Duration::from_nanos(
+                self.total_queue_wait_nanos.load(Ordering::Relaxed),
+            ),
+            max_queue_wait_time: Duration::from_nanos(
+                self.max_queue_wait_nanos.load(Ordering::Relaxed),
+            ),
+            dropped_permits: self.dropped_permits.load(Ordering::Relaxed),
         }
     }
 
     fn idle_duration(&self) -> Option<Duration> {
         let state = self.state.lock();
-        state.idle_since.map(|since| since.elapsed())
+        let now = self.config.clock.now();
+        state.idle_since.map(|since| now.duration_since(since))
     }
 }
 
@@ -518,7 +1091,7 @@ impl ReplenishingRateLimiter for TokenBucketRateLimiter {
     }
 
     fn replenishment_period(&self) -> Duration {
-        self.config.replenishment_period
+        self.state.lock().replenishment_period
     }
 
     fn try_replenish(&self) -> bool {
@@ -553,6 +1126,7 @@ impl Drop for TokenBucketRateLimiter {
 mod tests {
     use super::*;
     use crate::core::QueueProcessingOrder;
+    use crate::utils::ManualClock;
     use std::sync::Arc;
     use std::time::Duration;
     use tokio::time::sleep;
@@ -567,6 +1141,8 @@ mod tests {
             20,  // queue_limit
             QueueProcessingOrder::OldestFirst,
             true,  // auto_replenishment
+            0,     // one_time_burst
+            false, // replenish_fractionally
         )
         .unwrap();
 
@@ -602,6 +1178,35 @@ mod tests {
         assert!(lease4.is_acquired());
     }
 
+    #[test]
+    fn test_retry_after_accessor_matches_raw_metadata() {
+        let options = TokenBucketRateLimiterOptions::new(
+            1,
+            1,
+            Duration::from_millis(100),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false,
+            0,
+            false,
+        )
+        .unwrap();
+
+        let limiter = TokenBucketRateLimiter::new(options).unwrap();
+
+        let lease1 = limiter.attempt_acquire(1).unwrap();
+        assert!(lease1.is_acquired());
+        assert_eq!(lease1.retry_after(), None);
+
+        let lease2 = limiter.attempt_acquire(1).unwrap();
+        assert!(!lease2.is_acquired());
+        assert_eq!(
+            lease2.retry_after(),
+            lease2.try_get_metadata::<Duration>("RetryAfter").copied()
+        );
+        assert!(lease2.retry_after().is_some());
+    }
+
     #[tokio::test]
     async fn test_manual_replenishment() {
         // Create limiter with manual replenishment
@@ -612,6 +1217,8 @@ mod tests {
             20,  // queue_limit
             QueueProcessingOrder::OldestFirst,
             false,  // auto_replenishment - MANUAL mode
+            0,      // one_time_burst
+            false,  // replenish_fractionally
         )
         .unwrap();
 
@@ -636,6 +1243,37 @@ mod tests {
         assert!(lease3.is_acquired());
     }
 
+    #[tokio::test]
+    async fn test_lazy_replenish_without_spawned_timer() {
+        // Auto-replenishing bucket, but `run_replenishment_timer` is never
+        // spawned - tokens should still accrue lazily on the next acquire.
+        let options = TokenBucketRateLimiterOptions::new(
+            10,
+            5,
+            Duration::from_millis(100),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            true,
+            0,
+            false,
+        )
+        .unwrap();
+
+        let limiter = TokenBucketRateLimiter::new(options).unwrap();
+
+        let lease1 = limiter.attempt_acquire(10).unwrap();
+        assert!(lease1.is_acquired());
+
+        let lease2 = limiter.attempt_acquire(1).unwrap();
+        assert!(!lease2.is_acquired());
+
+        sleep(Duration::from_millis(110)).await;
+
+        // No timer was ever spawned; the upcoming call should lazily refill.
+        let lease3 = limiter.attempt_acquire(5).unwrap();
+        assert!(lease3.is_acquired());
+    }
+
     #[test]
     fn test_auto_replenishment_prevents_manual() {
         let options = TokenBucketRateLimiterOptions::new(
@@ -645,6 +1283,8 @@ mod tests {
             20,  // queue_limit
             QueueProcessingOrder::OldestFirst,
             true,  // auto_replenishment - AUTO mode
+            0,     // one_time_burst
+            false, // replenish_fractionally
         )
         .unwrap();
 
@@ -653,4 +1293,654 @@ mod tests {
         // Should return false when auto-replenishment is enabled
         assert!(!limiter.try_replenish());
     }
+
+    #[test]
+    fn test_one_time_burst_drawn_before_regular_bucket_and_never_refilled() {
+        let options = TokenBucketRateLimiterOptions::new(
+            5,   // token_limit
+            5,   // tokens_per_period
+            Duration::from_millis(100), // replenishment_period
+            0,   // queue_limit
+            QueueProcessingOrder::OldestFirst,
+            false, // auto_replenishment - manual mode
+            10,    // one_time_burst
+            false, // replenish_fractionally
+        )
+        .unwrap();
+
+        let limiter = TokenBucketRateLimiter::new(options).unwrap();
+
+        // Combined statistics report regular + burst up front.
+        assert_eq!(limiter.get_statistics().current_available_permits, 15);
+
+        // A lease larger than `token_limit` alone succeeds by drawing from
+        // the burst first.
+        let lease1 = limiter.attempt_acquire(10).unwrap();
+        assert!(lease1.is_acquired());
+        assert_eq!(limiter.get_statistics().current_available_permits, 5);
+
+        // Burst is now exhausted; only the regular bucket remains.
+        let lease2 = limiter.attempt_acquire(5).unwrap();
+        assert!(lease2.is_acquired());
+        assert_eq!(limiter.get_statistics().current_available_permits, 0);
+
+        // Manual replenishment restores the regular bucket up to
+        // `token_limit`, but never restores the one-time burst.
+        assert!(limiter.try_replenish());
+        assert_eq!(limiter.get_statistics().current_available_permits, 5);
+
+        let lease3 = limiter.attempt_acquire(6).unwrap();
+        assert!(!lease3.is_acquired());
+    }
+
+    #[test]
+    fn test_one_time_burst_remaining_tracks_draw_down_independently() {
+        let options = TokenBucketRateLimiterOptions::new(
+            5,
+            5,
+            Duration::from_millis(100),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false,
+            10,
+            false,
+        )
+        .unwrap();
+        let limiter = TokenBucketRateLimiter::new(options).unwrap();
+        assert_eq!(limiter.one_time_burst_remaining(), 10);
+
+        let lease = limiter.attempt_acquire(4).unwrap();
+        assert!(lease.is_acquired());
+        assert_eq!(limiter.one_time_burst_remaining(), 6);
+
+        // Replenishing the regular bucket doesn't touch the burst.
+        assert!(limiter.try_replenish());
+        assert_eq!(limiter.one_time_burst_remaining(), 6);
+    }
+
+    #[test]
+    fn test_zero_token_limit_with_burst_is_valid() {
+        // `token_limit == 0` alone is invalid, but it becomes a usable
+        // burst-only configuration once `one_time_burst` is nonzero.
+        let options = TokenBucketRateLimiterOptions::new(
+            0,  // token_limit
+            1,  // tokens_per_period
+            Duration::from_millis(100),
+            0,  // queue_limit
+            QueueProcessingOrder::OldestFirst,
+            false,
+            3,  // one_time_burst
+            false,
+        )
+        .unwrap();
+
+        let limiter = TokenBucketRateLimiter::new(options).unwrap();
+        assert_eq!(limiter.get_statistics().current_available_permits, 3);
+
+        let lease = limiter.attempt_acquire(3).unwrap();
+        assert!(lease.is_acquired());
+        assert_eq!(limiter.get_statistics().current_available_permits, 0);
+
+        // Burst is exhausted and there's no regular bucket, so further
+        // acquisitions within capacity simply fail rather than erroring.
+        let lease_after_exhaustion = limiter.attempt_acquire(1).unwrap();
+        assert!(!lease_after_exhaustion.is_acquired());
+
+        // A request above `token_limit + one_time_burst` is rejected as
+        // exceeding capacity.
+        let err = limiter.attempt_acquire(4).unwrap_err();
+        match err {
+            RateLimitError::PermitCountExceeded(requested, capacity) => {
+                assert_eq!(requested, 4);
+                assert_eq!(capacity, 3);
+            }
+            other => panic!("expected PermitCountExceeded, got {other:?}"),
+        }
+
+        // Both `token_limit` and `one_time_burst` being 0 is rejected.
+        let err = TokenBucketRateLimiterOptions::new(
+            0,
+            0,
+            Duration::from_millis(100),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false,
+            0,
+            false,
+        )
+        .unwrap_err();
+        assert!(matches!(err, RateLimitError::InvalidParameter(_)));
+    }
+
+    #[tokio::test]
+    async fn test_manual_fractional_replenishment_accrues_proportionally() {
+        // 10 tokens per 100ms period manually, fractional mode: a ~55ms wait
+        // should accrue roughly half a period's worth of tokens (5), not a
+        // full `tokens_per_period` as the flat-add mode would.
+        let options = TokenBucketRateLimiterOptions::new(
+            10,
+            10,
+            Duration::from_millis(100),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false, // auto_replenishment - manual mode
+            0,     // one_time_burst
+            true,  // replenish_fractionally
+        )
+        .unwrap();
+
+        let limiter = TokenBucketRateLimiter::new(options).unwrap();
+
+        let lease1 = limiter.attempt_acquire(10).unwrap();
+        assert!(lease1.is_acquired());
+
+        sleep(Duration::from_millis(55)).await;
+        assert!(limiter.try_replenish());
+        let available = limiter.get_statistics().current_available_permits;
+        assert!(
+            (4..=6).contains(&available),
+            "expected ~5 tokens accrued from a half period, got {available}"
+        );
+
+        // The fractional remainder carries forward: waiting the other ~55ms
+        // should be enough to accrue the rest of the period.
+        sleep(Duration::from_millis(55)).await;
+        assert!(limiter.try_replenish());
+        assert_eq!(limiter.get_statistics().current_available_permits, 10);
+    }
+
+    #[test]
+    fn test_replenish_records_dropped_permits_when_bucket_is_already_full() {
+        let options = TokenBucketRateLimiterOptions::new(
+            10,
+            5,
+            Duration::from_millis(100),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false, // auto_replenishment - manual mode
+            0,     // one_time_burst
+            false, // replenish_fractionally
+        )
+        .unwrap();
+
+        let limiter = TokenBucketRateLimiter::new(options).unwrap();
+
+        // Bucket starts full: the flat `tokens_per_period` add has nowhere
+        // to go and the whole amount is dropped.
+        assert!(limiter.try_replenish());
+        assert_eq!(limiter.get_statistics().dropped_permits, 5);
+
+        // Partially draining the bucket means only the overflow beyond
+        // `token_limit` is dropped, not the whole replenishment.
+        let lease = limiter.attempt_acquire(8).unwrap();
+        assert!(lease.is_acquired());
+
+        assert!(limiter.try_replenish()); // 2 + 5 = 7, no overflow
+        assert_eq!(limiter.get_statistics().current_available_permits, 7);
+        assert_eq!(limiter.get_statistics().dropped_permits, 5);
+
+        assert!(limiter.try_replenish()); // 7 + 5 = 12, caps at 10, drops 2
+        assert_eq!(limiter.get_statistics().current_available_permits, 10);
+        assert_eq!(limiter.get_statistics().dropped_permits, 5 + 2);
+    }
+
+    #[test]
+    fn test_update_shrinks_limit_and_clamps_available_permits() {
+        let options = TokenBucketRateLimiterOptions::new(
+            10,
+            5,
+            Duration::from_millis(100),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false, // auto_replenishment - manual mode
+            0,     // one_time_burst
+            false, // replenish_fractionally
+        )
+        .unwrap();
+
+        let limiter = TokenBucketRateLimiter::new(options).unwrap();
+        assert_eq!(limiter.get_statistics().current_available_permits, 10);
+
+        // Shrinking token_limit below the current surplus clamps it down.
+        limiter
+            .update(BucketUpdate {
+                token_limit: Some(4),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(limiter.get_statistics().current_available_permits, 4);
+
+        // Capacity now reflects the new limit.
+        let err = limiter.attempt_acquire(5).unwrap_err();
+        assert!(matches!(
+            err,
+            RateLimitError::PermitCountExceeded(5, 4)
+        ));
+
+        // tokens_per_period and replenishment_period are also live-tunable.
+        limiter
+            .update(BucketUpdate {
+                tokens_per_period: Some(2),
+                replenishment_period: Some(Duration::from_millis(50)),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(limiter.replenishment_period(), Duration::from_millis(50));
+
+        let lease = limiter.attempt_acquire(4).unwrap();
+        assert!(lease.is_acquired());
+        assert!(limiter.try_replenish());
+        assert_eq!(limiter.get_statistics().current_available_permits, 2);
+    }
+
+    #[test]
+    fn test_update_rejects_invalid_configuration() {
+        let options = TokenBucketRateLimiterOptions::new(
+            10,
+            5,
+            Duration::from_millis(100),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false,
+            0,
+            false,
+        )
+        .unwrap();
+
+        let limiter = TokenBucketRateLimiter::new(options).unwrap();
+
+        let err = limiter
+            .update(BucketUpdate {
+                tokens_per_period: Some(0),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert!(matches!(err, RateLimitError::InvalidParameter(_)));
+
+        // Rejected update leaves the bucket unchanged.
+        assert_eq!(limiter.get_statistics().current_available_permits, 10);
+        assert_eq!(limiter.replenishment_period(), Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_update_fails_queued_requests_that_now_exceed_capacity() {
+        let options = TokenBucketRateLimiterOptions::new(
+            10,
+            5,
+            Duration::from_millis(100),
+            20,
+            QueueProcessingOrder::OldestFirst,
+            false, // auto_replenishment - manual mode
+            0,     // one_time_burst
+            false, // replenish_fractionally
+        )
+        .unwrap();
+
+        let limiter = Arc::new(TokenBucketRateLimiter::new(options).unwrap());
+
+        // Drain the bucket, then queue a request for 8 tokens.
+        let lease = limiter.attempt_acquire(10).unwrap();
+        assert!(lease.is_acquired());
+
+        let limiter_clone = Arc::clone(&limiter);
+        let waiter = tokio::spawn(async move { limiter_clone.acquire_async(8, None).await });
+
+        // Give the waiter a moment to enqueue, then shrink the limit below
+        // what it's asking for.
+        sleep(Duration::from_millis(20)).await;
+        limiter
+            .update(BucketUpdate {
+                token_limit: Some(5),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let result = waiter.await.unwrap();
+        let err = result.unwrap_err();
+        assert!(matches!(
+            err,
+            RateLimitError::PermitCountExceeded(8, 5)
+        ));
+    }
+
+    #[test]
+    fn test_available_tokens_reflects_fractional_state() {
+        let options = TokenBucketRateLimiterOptions::new(
+            10,
+            10,
+            Duration::from_secs(60),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false,
+            0,
+            false,
+        )
+        .unwrap();
+        let limiter = TokenBucketRateLimiter::new(options).unwrap();
+
+        let lease = limiter.attempt_acquire(3).unwrap();
+        assert!(lease.is_acquired());
+        assert_eq!(limiter.available_tokens(), 7.0);
+    }
+
+    #[test]
+    fn test_set_available_tokens_rejects_amount_above_token_limit() {
+        let options = TokenBucketRateLimiterOptions::new(
+            10,
+            10,
+            Duration::from_secs(60),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false,
+            0,
+            false,
+        )
+        .unwrap();
+        let limiter = TokenBucketRateLimiter::new(options).unwrap();
+
+        let err = limiter.set_available_tokens(11.0).unwrap_err();
+        assert!(matches!(err, RateLimitError::InvalidParameter(_)));
+        // Unchanged on error.
+        assert_eq!(limiter.available_tokens(), 10.0);
+    }
+
+    #[tokio::test]
+    async fn test_set_available_tokens_raising_serves_queued_waiters() {
+        let options = TokenBucketRateLimiterOptions::new(
+            5,
+            5,
+            Duration::from_secs(60),
+            10,
+            QueueProcessingOrder::OldestFirst,
+            false,
+            0,
+            false,
+        )
+        .unwrap();
+        let limiter = Arc::new(TokenBucketRateLimiter::new(options).unwrap());
+
+        let lease = limiter.attempt_acquire(5).unwrap();
+        assert!(lease.is_acquired());
+
+        let limiter_clone = Arc::clone(&limiter);
+        let waiter = tokio::spawn(async move { limiter_clone.acquire_async(4, None).await });
+
+        sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        limiter.set_available_tokens(4.0).unwrap();
+
+        let lease2 = tokio::time::timeout(Duration::from_millis(200), waiter)
+            .await
+            .expect("queued request should resolve once tokens are set directly")
+            .unwrap()
+            .unwrap();
+        assert!(lease2.is_acquired());
+    }
+
+    #[test]
+    fn test_manual_replenishment_with_manual_clock() {
+        // Same scenario as `test_manual_replenishment`, but driven by a
+        // `ManualClock` instead of a real sleep - elapsed periods are
+        // computed against simulated time deterministically and instantly.
+        let clock = ManualClock::new();
+        let options = TokenBucketRateLimiterOptions {
+            clock: Arc::new(clock.clone()),
+            ..TokenBucketRateLimiterOptions::new(
+                10,  // token_limit
+                5,   // tokens_per_period
+                Duration::from_millis(100), // replenishment_period
+                0,   // queue_limit
+                QueueProcessingOrder::OldestFirst,
+                false, // auto_replenishment - MANUAL mode
+                0,     // one_time_burst
+                false, // replenish_fractionally
+            )
+            .unwrap()
+        };
+
+        let limiter = TokenBucketRateLimiter::new(options).unwrap();
+
+        let lease1 = limiter.attempt_acquire(10).unwrap();
+        assert!(lease1.is_acquired());
+        assert_eq!(limiter.available_tokens(), 0.0);
+
+        // Advance past the replenishment period without any real waiting.
+        clock.advance(Duration::from_millis(100));
+        assert!(limiter.try_replenish());
+        assert_eq!(limiter.available_tokens(), 5.0);
+    }
+
+    #[test]
+    fn test_fractional_replenishment_with_manual_clock() {
+        // Same intent as `test_manual_fractional_replenishment_accrues_proportionally`,
+        // but deterministic: elapsed periods are computed against simulated
+        // time instead of a real sleep.
+        let clock = ManualClock::new();
+        let options = TokenBucketRateLimiterOptions {
+            clock: Arc::new(clock.clone()),
+            ..TokenBucketRateLimiterOptions::new(
+                10,
+                10,
+                Duration::from_millis(100),
+                0,
+                QueueProcessingOrder::OldestFirst,
+                false, // auto_replenishment - MANUAL mode
+                0,
+                true, // replenish_fractionally
+            )
+            .unwrap()
+        };
+
+        let limiter = TokenBucketRateLimiter::new(options).unwrap();
+
+        let lease = limiter.attempt_acquire(10).unwrap();
+        assert!(lease.is_acquired());
+
+        // Half a period elapses: 10 tokens/period at 50% progress = 5 tokens.
+        clock.advance(Duration::from_millis(50));
+        assert!(limiter.try_replenish());
+        assert_eq!(limiter.available_tokens(), 5.0);
+    }
+
+    #[tokio::test]
+    async fn test_ready_notifier_wakes_on_replenish_crossing_threshold() {
+        let options = TokenBucketRateLimiterOptions::new(
+            5,
+            5,
+            Duration::from_millis(100),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false, // auto_replenishment - manual mode
+            0,
+            false,
+        )
+        .unwrap();
+        let limiter = Arc::new(TokenBucketRateLimiter::new(options).unwrap());
+
+        let lease = limiter.attempt_acquire(5).unwrap();
+        assert!(lease.is_acquired());
+
+        let waiter = {
+            let limiter = Arc::clone(&limiter);
+            tokio::spawn(async move {
+                limiter.ready_notifier().notified().await;
+            })
+        };
+
+        sleep(Duration::from_millis(10)).await;
+        assert!(!waiter.is_finished());
+
+        assert!(limiter.try_replenish());
+
+        tokio::time::timeout(Duration::from_millis(200), waiter)
+            .await
+            .expect("ready_notifier should fire once tokens become available")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ready_notifier_does_not_fire_while_still_below_threshold() {
+        let options = TokenBucketRateLimiterOptions {
+            ready_threshold: 5,
+            ..TokenBucketRateLimiterOptions::new(
+                10,
+                1,
+                Duration::from_millis(100),
+                0,
+                QueueProcessingOrder::OldestFirst,
+                false, // auto_replenishment - manual mode
+                0,
+                false,
+            )
+            .unwrap()
+        };
+        let limiter = Arc::new(TokenBucketRateLimiter::new(options).unwrap());
+
+        let lease = limiter.attempt_acquire(10).unwrap();
+        assert!(lease.is_acquired());
+
+        let waiter = {
+            let limiter = Arc::clone(&limiter);
+            tokio::spawn(async move {
+                limiter.ready_notifier().notified().await;
+            })
+        };
+
+        // Two replenishes (0 -> 1 -> 2 tokens) both stay below the
+        // threshold of 5, so the waiter must not be woken by either.
+        assert!(limiter.try_replenish());
+        assert!(limiter.try_replenish());
+        sleep(Duration::from_millis(10)).await;
+        assert!(!waiter.is_finished());
+
+        // Three more replenishes (2 -> 5) cross the threshold and wake it.
+        for _ in 0..3 {
+            assert!(limiter.try_replenish());
+        }
+        tokio::time::timeout(Duration::from_millis(200), waiter)
+            .await
+            .expect("ready_notifier should fire once available_tokens reaches the threshold")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_set_available_tokens_raising_across_threshold_notifies() {
+        let options = TokenBucketRateLimiterOptions::new(
+            10,
+            10,
+            Duration::from_secs(60),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false,
+            0,
+            false,
+        )
+        .unwrap();
+        let limiter = Arc::new(TokenBucketRateLimiter::new(options).unwrap());
+        limiter.set_available_tokens(0.0).unwrap();
+
+        let waiter = {
+            let limiter = Arc::clone(&limiter);
+            tokio::spawn(async move {
+                limiter.ready_notifier().notified().await;
+            })
+        };
+
+        sleep(Duration::from_millis(10)).await;
+        assert!(!waiter.is_finished());
+
+        limiter.set_available_tokens(1.0).unwrap();
+
+        tokio::time::timeout(Duration::from_millis(200), waiter)
+            .await
+            .expect("ready_notifier should fire once set_available_tokens crosses the threshold")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_preconfig_burst_allows_spending_most_of_the_window_at_once() {
+        let options = TokenBucketRateLimiterOptions::preconfig_burst(
+            100.0,
+            Duration::from_secs(1),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(options.tokens_per_period, 100);
+        assert_eq!(options.token_limit, 90);
+        assert_eq!(options.replenishment_period, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_preconfig_throughput_keeps_bucket_capacity_small() {
+        let options = TokenBucketRateLimiterOptions::preconfig_throughput(
+            100.0,
+            Duration::from_secs(1),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(options.tokens_per_period, 100);
+        assert_eq!(options.token_limit, 10);
+        assert_eq!(options.replenishment_period, Duration::from_millis(1050));
+    }
+
+    #[test]
+    fn test_preconfig_burst_and_throughput_differ_for_the_same_target_rate() {
+        let burst = TokenBucketRateLimiterOptions::preconfig_burst(
+            100.0,
+            Duration::from_secs(1),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false,
+            false,
+        )
+        .unwrap();
+        let throughput = TokenBucketRateLimiterOptions::preconfig_throughput(
+            100.0,
+            Duration::from_secs(1),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(burst.tokens_per_period, throughput.tokens_per_period);
+        assert_ne!(burst.token_limit, throughput.token_limit);
+        assert_ne!(burst.replenishment_period, throughput.replenishment_period);
+    }
+
+    #[test]
+    fn test_preconfig_rejects_non_positive_target_rate() {
+        let result = TokenBucketRateLimiterOptions::preconfig_burst(
+            0.0,
+            Duration::from_secs(1),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false,
+            false,
+        );
+        assert!(matches!(result, Err(RateLimitError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_preconfig_rejects_zero_window() {
+        let result = TokenBucketRateLimiterOptions::preconfig_throughput(
+            100.0,
+            Duration::ZERO,
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false,
+            false,
+        );
+        assert!(matches!(result, Err(RateLimitError::InvalidParameter(_))));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,158 @@
+use crate::partitioned::{create_per_key_token_bucket, PartitionedRateLimiter};
+use crate::{QueueProcessingOrder, RateLimitError, RateLimitLease, RateLimiterStatistics};
+use std::hash::Hash;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// A per-key `TokenBucketRateLimiter` registry, sized for the classic
+/// per-client web rate-limit pattern (e.g. keyed by client IP or API token).
+///
+/// This is a thin, purpose-named wrapper around
+/// `PartitionedRateLimiter::<K, K>` plus `create_per_key_token_bucket`: every
+/// key gets its own independently-replenishing token bucket sharing one
+/// template configuration, and `cleanup_idle`/`start_cleanup` already evict
+/// any bucket whose `idle_duration()` confirms it's both unused past the
+/// configured TTL *and* currently empty, so no client loses budget mid-flight.
+/// Reach for `PartitionedRateLimiter` directly instead if keys need
+/// independently-configured limiters or a resource type distinct from the
+/// partition key.
+pub struct KeyedTokenBucketRateLimiter<K>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+{
+    inner: PartitionedRateLimiter<K, K>,
+}
+
+impl<K> KeyedTokenBucketRateLimiter<K>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+{
+    /// Creates a registry where every key gets its own token bucket built
+    /// from the same template configuration (see
+    /// `TokenBucketRateLimiterOptions::new` for field meanings), evicting
+    /// idle, empty buckets that haven't been used for `idle_time_limit`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        token_limit: u32,
+        tokens_per_period: u32,
+        replenishment_period: Duration,
+        queue_limit: u32,
+        queue_processing_order: QueueProcessingOrder,
+        auto_replenishment: bool,
+        idle_time_limit: Duration,
+    ) -> Self {
+        Self {
+            inner: PartitionedRateLimiter::with_idle_limit(
+                create_per_key_token_bucket::<K>(
+                    token_limit,
+                    tokens_per_period,
+                    replenishment_period,
+                    queue_limit,
+                    queue_processing_order,
+                    auto_replenishment,
+                ),
+                idle_time_limit,
+            ),
+        }
+    }
+
+    /// Attempts to acquire `permit_count` tokens from `key`'s bucket,
+    /// creating it from the template configuration if this is the first
+    /// request seen for `key`.
+    pub fn acquire_for(&self, key: K, permit_count: u32) -> Result<RateLimitLease, RateLimitError> {
+        self.inner.attempt_acquire(&key, permit_count)
+    }
+
+    /// Asynchronously acquires `permit_count` tokens from `key`'s bucket,
+    /// queueing if it's currently exhausted.
+    pub async fn acquire_for_async(
+        &self,
+        key: K,
+        permit_count: u32,
+        cancellation_token: Option<CancellationToken>,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        self.inner.acquire_async(&key, permit_count, cancellation_token).await
+    }
+
+    /// Aggregates statistics across every live per-key bucket.
+    pub fn get_statistics(&self) -> RateLimiterStatistics {
+        self.inner.aggregate_statistics()
+    }
+
+    /// The number of keys with a currently-cached bucket.
+    pub fn partition_count(&self) -> usize {
+        self.inner.partition_count()
+    }
+
+    /// Synchronously evicts buckets that are both idle past the configured
+    /// TTL and empty. Returns the number of buckets removed.
+    pub fn cleanup_idle(&self) -> usize {
+        self.inner.cleanup_idle()
+    }
+
+    /// Spawns a background task that calls `cleanup_idle` on a fixed
+    /// `interval` until this limiter is dropped.
+    pub fn start_cleanup(&self, interval: Duration) {
+        self.inner.start_cleanup(interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(idle_time_limit: Duration) -> KeyedTokenBucketRateLimiter<String> {
+        KeyedTokenBucketRateLimiter::new(
+            5,
+            5,
+            Duration::from_secs(60),
+            0,
+            QueueProcessingOrder::OldestFirst,
+            false,
+            idle_time_limit,
+        )
+    }
+
+    #[test]
+    fn test_keys_get_independent_buckets() {
+        let limiter = limiter(Duration::from_secs(10));
+
+        let lease1 = limiter.acquire_for("user1".to_string(), 5).unwrap();
+        assert!(lease1.is_acquired());
+
+        // "user2" is untouched by "user1" draining its own bucket.
+        let lease2 = limiter.acquire_for("user2".to_string(), 5).unwrap();
+        assert!(lease2.is_acquired());
+
+        let lease3 = limiter.acquire_for("user1".to_string(), 1).unwrap();
+        assert!(!lease3.is_acquired());
+    }
+
+    #[test]
+    fn test_get_statistics_aggregates_across_keys() {
+        let limiter = limiter(Duration::from_secs(10));
+        let _ = limiter.acquire_for("user1".to_string(), 2);
+        let _ = limiter.acquire_for("user2".to_string(), 1);
+
+        assert_eq!(limiter.get_statistics().current_available_permits, 3 + 4);
+    }
+
+    #[test]
+    fn test_cleanup_idle_evicts_only_fully_replenished_idle_keys() {
+        let limiter = limiter(Duration::ZERO);
+
+        // A zero-cost acquire still creates the key's bucket without
+        // drawing it down, so it stays fully idle from the start.
+        let _ = limiter.acquire_for("idle".to_string(), 0);
+        // Draining "in-use" leaves its bucket short of full.
+        let _ = limiter.acquire_for("in-use".to_string(), 2);
+        assert_eq!(limiter.partition_count(), 2);
+
+        // "in-use" still has tokens drawn down, so `idle_duration()` returns
+        // `None` for it and cleanup must leave it alone; "idle" is full and
+        // gets evicted.
+        let removed = limiter.cleanup_idle();
+        assert_eq!(removed, 1);
+        assert_eq!(limiter.partition_count(), 1);
+    }
+}
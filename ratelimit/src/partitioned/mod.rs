@@ -1,13 +1,29 @@
 //! Partitioned rate limiting for key-based scenarios.
 //!
 //! Allows different rate limits per partition key (e.g., per user, per IP address).
+//!
+//! `ServerFeedbackPartitionedLimiter` additionally reconciles each key's
+//! limiter against a downstream API's own advertised rate limit state.
+//!
+//! `KeyedTokenBucketRateLimiter` is a purpose-named convenience wrapper
+//! around `PartitionedRateLimiter` for the common case of one token bucket
+//! per key (e.g. per client IP or API token).
 
+mod keyed_token_bucket;
 mod partitioned_impl;
+mod server_feedback;
 
+pub use keyed_token_bucket::KeyedTokenBucketRateLimiter;
 pub use partitioned_impl::{
     PartitionedRateLimiter,
+    CombinedPartitionedRateLimiter,
     create_per_key_token_bucket,
     create_per_key_concurrency,
     create_per_key_fixed_window,
     create_per_key_sliding_window,
-};
\ No newline at end of file
+    create_per_key_multi_bucket,
+};
+pub use server_feedback::ServerFeedbackPartitionedLimiter;
+/// Re-exported here so callers using the partitioned adapter don't also need
+/// to import from `ratelimit::limiters`.
+pub use crate::limiters::RateLimitHeaders;
\ No newline at end of file
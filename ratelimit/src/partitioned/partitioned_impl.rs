@@ -1,14 +1,55 @@
 use crate::{RateLimiter, RateLimitLease, RateLimiterStatistics, RateLimitError, QueueProcessingOrder};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use parking_lot::Mutex;
 use tokio_util::sync::CancellationToken;
 
 /// Type alias for the partitioner function that maps resources to partition keys and limiters.
 type PartitionerFn<TResource, TKey> = dyn Fn(&TResource) -> (TKey, Arc<dyn RateLimiter>) + Send + Sync;
 
+/// A cached per-key limiter plus the last time it was looked up via
+/// `get_limiter`, used to find partitions that cleanup can safely evict.
+struct CachedLimiter {
+    limiter: Arc<dyn RateLimiter>,
+    last_accessed: Instant,
+}
+
+/// Rolling request count used to detect a key that has become "hot" enough
+/// to warrant redirecting it to the shared overflow limiter.
+struct OverflowTracker {
+    /// Start of the current counting window.
+    window_start: Instant,
+    /// Requests seen for this key since `window_start`.
+    count: u32,
+    /// Whether the key is currently classified as overflowing.
+    overflowing: bool,
+}
+
+/// Configuration for the "localized overflow" pattern: when a single
+/// partition key's request rate crosses `threshold` within `window`, its
+/// traffic is redirected to `overflow_limiter` instead of its own
+/// per-key limiter, protecting the rest of the partitions from a single
+/// hot key.
+struct OverflowState<TKey> {
+    /// Shared limiter that absorbs traffic from overflowing keys.
+    overflow_limiter: Arc<dyn RateLimiter>,
+    /// Number of requests within `window` that marks a key as overflowing.
+    threshold: u32,
+    /// Length of the rolling window used to count requests per key.
+    window: Duration,
+    /// Per-key rolling counters backing overflow detection.
+    trackers: Mutex<HashMap<TKey, OverflowTracker>>,
+    /// Keys known in advance to be hot; these bypass detection entirely and
+    /// always go straight to `overflow_limiter`.
+    forced_keys: Mutex<HashSet<TKey>>,
+    /// Total number of requests that were routed to `overflow_limiter`,
+    /// whether forced or detected.
+    overflow_hits: AtomicU64,
+}
+
 /// A rate limiter that partitions resources and applies different rate limit policies per partition.
 ///
 /// This limiter creates separate rate limiter instances for each unique partition key,
@@ -29,7 +70,7 @@ type PartitionerFn<TResource, TKey> = dyn Fn(&TResource) -> (TKey, Arc<dyn RateL
 /// let limiter = PartitionedRateLimiter::new(
 ///     |user_id: &String| {
 ///         // Each user gets their own token bucket with 100 requests per minute
-///         let options = TokenBucketRateLimiterOptions::new(100, 100, Duration::from_secs(60), 1, QueueProcessingOrder::OldestFirst, false).unwrap();
+///         let options = TokenBucketRateLimiterOptions::new(100, 100, Duration::from_secs(60), 1, QueueProcessingOrder::OldestFirst, false, 0, false).unwrap();
 ///         (
 ///             user_id.clone(),
 ///             Arc::new(TokenBucketRateLimiter::new(options).unwrap()) as Arc<dyn RateLimiter>
@@ -47,14 +88,21 @@ where
     TResource: Send + Sync,
 {
     /// Map of partition keys to their corresponding rate limiters
-    limiters: Arc<Mutex<HashMap<TKey, Arc<dyn RateLimiter>>>>,
+    limiters: Arc<Mutex<HashMap<TKey, CachedLimiter>>>,
 
     /// Function that maps a resource to a partition key and creates a limiter for new keys
     partitioner: Arc<PartitionerFn<TResource, TKey>>,
 
-    /// Optional idle time limit for limiter cleanup (not implemented in v1)
-    #[allow(dead_code)]
+    /// How long a partition can go unused (see `cleanup_idle`/`start_cleanup`) before it's
+    /// eligible for eviction from the `limiters` map.
     idle_time_limit: Duration,
+
+    /// Cancellation token stopping the background task spawned by `start_cleanup`.
+    cleanup_cancel: CancellationToken,
+
+    /// Hot-key overflow detection and redirection, if configured via
+    /// `with_overflow`.
+    overflow: Option<OverflowState<TKey>>,
 }
 
 impl<TResource, TKey> PartitionedRateLimiter<TResource, TKey>
@@ -81,7 +129,9 @@ where
     ///
     /// # Arguments
     /// - `partitioner`: Function that maps resources to partition keys and creates limiters
-    /// - `idle_time_limit`: How long a limiter can be idle before cleanup (not implemented in v1)
+    /// - `idle_time_limit`: How long a partition can go unused before `cleanup_idle`/
+    ///   `start_cleanup` are allowed to evict it (see those methods for the full
+    ///   eligibility criteria)
     pub fn with_idle_limit<F>(partitioner: F, idle_time_limit: Duration) -> Self
     where
         F: Fn(&TResource) -> (TKey, Arc<dyn RateLimiter>) + Send + Sync + 'static,
@@ -90,53 +140,228 @@ where
             limiters: Arc::new(Mutex::new(HashMap::new())),
             partitioner: Arc::new(partitioner),
             idle_time_limit,
+            cleanup_cancel: CancellationToken::new(),
+            overflow: None,
+        }
+    }
+
+    /// Creates a new partitioned rate limiter with hot-key overflow
+    /// detection and redirection.
+    ///
+    /// # Arguments
+    /// - `partitioner`: Function that maps resources to partition keys and creates limiters
+    /// - `idle_time_limit`: How long a partition can go unused before `cleanup_idle`/
+    ///   `start_cleanup` are allowed to evict it
+    /// - `overflow_limiter`: Shared limiter that absorbs traffic from keys
+    ///   classified as overflowing, instead of each hot key exhausting its
+    ///   own per-key limiter and starving the others
+    /// - `overflow_threshold`: Number of requests within `overflow_window`
+    ///   that marks a key as overflowing
+    /// - `overflow_window`: Length of the rolling window used to count
+    ///   requests per key for overflow detection
+    ///
+    /// Use `add_forced_overflow_key`/`remove_forced_overflow_key` to mark
+    /// keys known in advance to be hot, bypassing detection entirely.
+    pub fn with_overflow<F>(
+        partitioner: F,
+        idle_time_limit: Duration,
+        overflow_limiter: Arc<dyn RateLimiter>,
+        overflow_threshold: u32,
+        overflow_window: Duration,
+    ) -> Self
+    where
+        F: Fn(&TResource) -> (TKey, Arc<dyn RateLimiter>) + Send + Sync + 'static,
+    {
+        Self {
+            limiters: Arc::new(Mutex::new(HashMap::new())),
+            partitioner: Arc::new(partitioner),
+            idle_time_limit,
+            cleanup_cancel: CancellationToken::new(),
+            overflow: Some(OverflowState {
+                overflow_limiter,
+                threshold: overflow_threshold,
+                window: overflow_window,
+                trackers: Mutex::new(HashMap::new()),
+                forced_keys: Mutex::new(HashSet::new()),
+                overflow_hits: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Marks `key` as hot in advance, bypassing overflow detection so it
+    /// always routes straight to the overflow limiter. No-op if this
+    /// limiter wasn't created with `with_overflow`.
+    pub fn add_forced_overflow_key(&self, key: TKey) {
+        if let Some(overflow) = &self.overflow {
+            overflow.forced_keys.lock().insert(key);
+        }
+    }
+
+    /// Removes a previously forced overflow key, returning it to normal
+    /// detection-based routing. No-op if this limiter wasn't created with
+    /// `with_overflow`.
+    pub fn remove_forced_overflow_key(&self, key: &TKey) {
+        if let Some(overflow) = &self.overflow {
+            overflow.forced_keys.lock().remove(key);
+        }
+    }
+
+    /// Returns whether the resource's partition key is currently routed to
+    /// the overflow limiter, either because it was forced or because it
+    /// crossed the detection threshold. Always `false` if this limiter
+    /// wasn't created with `with_overflow`.
+    pub fn is_overflowing(&self, resource: &TResource) -> bool {
+        let Some(overflow) = &self.overflow else {
+            return false;
+        };
+        let (key, _unused_limiter) = (self.partitioner)(resource);
+        if overflow.forced_keys.lock().contains(&key) {
+            return true;
+        }
+        overflow
+            .trackers
+            .lock()
+            .get(&key)
+            .is_some_and(|tracker| tracker.overflowing)
+    }
+
+    /// Total number of requests that have been routed to the overflow
+    /// limiter so far, whether forced or detected. Always `0` if this
+    /// limiter wasn't created with `with_overflow`.
+    pub fn overflow_hit_count(&self) -> u64 {
+        self.overflow
+            .as_ref()
+            .map(|overflow| overflow.overflow_hits.load(Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+
+    /// Resolves the limiter that should handle `resource`: the shared
+    /// overflow limiter if its key is forced or has crossed the detection
+    /// threshold, otherwise the resource's own per-key limiter.
+    ///
+    /// Also records this request against the key's rolling overflow
+    /// counter, so a single call both checks and updates overflow state.
+    fn resolve_limiter(&self, resource: &TResource) -> Arc<dyn RateLimiter> {
+        if let Some(overflow) = &self.overflow {
+            let (key, _unused_limiter) = (self.partitioner)(resource);
+
+            let forced = overflow.forced_keys.lock().contains(&key);
+            let overflowing = forced || Self::record_and_check_overflow(overflow, &key);
+
+            if overflowing {
+                overflow.overflow_hits.fetch_add(1, Ordering::Relaxed);
+                return overflow.overflow_limiter.clone();
+            }
+        }
+
+        self.get_limiter(resource)
+    }
+
+    /// Records a request against `key`'s rolling window counter, resetting
+    /// the window once it has elapsed, and returns whether the key is now
+    /// classified as overflowing.
+    fn record_and_check_overflow(overflow: &OverflowState<TKey>, key: &TKey) -> bool {
+        let mut trackers = overflow.trackers.lock();
+        let tracker = trackers.entry(key.clone()).or_insert_with(|| OverflowTracker {
+            window_start: Instant::now(),
+            count: 0,
+            overflowing: false,
+        });
+
+        if tracker.window_start.elapsed() >= overflow.window {
+            tracker.window_start = Instant::now();
+            tracker.count = 0;
+            tracker.overflowing = false;
+        }
+
+        tracker.count += 1;
+        if tracker.count > overflow.threshold {
+            tracker.overflowing = true;
         }
+        tracker.overflowing
     }
 
     /// Gets or creates a rate limiter for the given resource.
     ///
     /// If a limiter already exists for the partition key, it is returned.
     /// Otherwise, a new limiter is created using the partitioner function.
+    /// Either way, the partition's `last_accessed` timestamp is refreshed so
+    /// it isn't mistaken for idle by `cleanup_idle`.
     fn get_limiter(&self, resource: &TResource) -> Arc<dyn RateLimiter> {
         let (key, new_limiter) = (self.partitioner)(resource);
 
         let mut limiters = self.limiters.lock();
 
-        limiters
-            .entry(key)
-            .or_insert_with(|| new_limiter)
-            .clone()
+        let entry = limiters.entry(key).or_insert_with(|| CachedLimiter {
+            limiter: new_limiter,
+            last_accessed: Instant::now(),
+        });
+        entry.last_accessed = Instant::now();
+        entry.limiter.clone()
     }
 
     /// Attempts to acquire a lease for the given resource.
     ///
-    /// This delegates to the rate limiter for the resource's partition.
+    /// This delegates to the rate limiter for the resource's partition,
+    /// unless the key is overflowing (see `with_overflow`), in which case
+    /// it delegates to the shared overflow limiter instead.
     pub fn attempt_acquire(&self, resource: &TResource, permit_count: u32) -> Result<RateLimitLease, RateLimitError> {
-        let limiter = self.get_limiter(resource);
+        let limiter = self.resolve_limiter(resource);
         limiter.attempt_acquire(permit_count)
     }
 
     /// Asynchronously acquires a lease for the given resource.
     ///
-    /// This delegates to the rate limiter for the resource's partition.
+    /// This delegates to the rate limiter for the resource's partition,
+    /// unless the key is overflowing (see `with_overflow`), in which case
+    /// it delegates to the shared overflow limiter instead.
     pub async fn acquire_async(
         &self,
         resource: &TResource,
         permit_count: u32,
         cancellation_token: Option<CancellationToken>,
     ) -> Result<RateLimitLease, RateLimitError> {
-        let limiter = self.get_limiter(resource);
+        let limiter = self.resolve_limiter(resource);
         limiter.acquire_async(permit_count, cancellation_token).await
     }
 
-    /// Gets statistics for the given resource's partition.
+    /// Gets statistics for the given resource's partition (or the shared
+    /// overflow limiter, if the key is currently overflowing).
     ///
-    /// This delegates to the rate limiter for the resource's partition.
+    /// Reads, rather than records, overflow state - unlike `attempt_acquire`
+    /// and `acquire_async`, a statistics read must not itself count as a
+    /// request against the key's overflow window, or a metrics scraper
+    /// polling this method would drive keys into overflow redirection and
+    /// inflate `overflow_hit_count()` on its own.
     pub fn get_statistics(&self, resource: &TResource) -> RateLimiterStatistics {
-        let limiter = self.get_limiter(resource);
+        let limiter = self.resolve_limiter_readonly(resource);
         limiter.get_statistics()
     }
 
+    /// Resolves the limiter that should handle `resource` the same way
+    /// `resolve_limiter` does, but purely as a read: checks whether the
+    /// key is forced or already classified as overflowing without feeding
+    /// this call into the rolling overflow counter.
+    fn resolve_limiter_readonly(&self, resource: &TResource) -> Arc<dyn RateLimiter> {
+        if let Some(overflow) = &self.overflow {
+            let (key, _unused_limiter) = (self.partitioner)(resource);
+
+            let forced = overflow.forced_keys.lock().contains(&key);
+            let overflowing = forced
+                || overflow
+                    .trackers
+                    .lock()
+                    .get(&key)
+                    .is_some_and(|tracker| tracker.overflowing);
+
+            if overflowing {
+                return overflow.overflow_limiter.clone();
+            }
+        }
+
+        self.get_limiter(resource)
+    }
+
 
     /// Gets the number of active partitions.
     pub fn partition_count(&self) -> usize {
@@ -151,6 +376,265 @@ where
         let mut limiters = self.limiters.lock();
         limiters.clear();
     }
+
+    /// Lists the partition keys that currently have a cached limiter.
+    pub fn partition_keys(&self) -> Vec<TKey> {
+        let limiters = self.limiters.lock();
+        limiters.keys().cloned().collect()
+    }
+
+    /// Snapshots statistics for every live partition, keyed by partition
+    /// key, for per-key export to a metrics backend.
+    ///
+    /// Takes the partition map lock once to clone out the `Arc<dyn
+    /// RateLimiter>` handles, then releases it before calling
+    /// `get_statistics` on each one, so the lock isn't held for the
+    /// duration of a scan across thousands of partitions.
+    pub fn statistics_by_partition(&self) -> HashMap<TKey, RateLimiterStatistics> {
+        let snapshot: Vec<(TKey, Arc<dyn RateLimiter>)> = {
+            let limiters = self.limiters.lock();
+            limiters
+                .iter()
+                .map(|(key, entry)| (key.clone(), entry.limiter.clone()))
+                .collect()
+        };
+
+        snapshot
+            .into_iter()
+            .map(|(key, limiter)| (key, limiter.get_statistics()))
+            .collect()
+    }
+
+    /// Aggregates statistics across every live partition.
+    ///
+    /// Sums `current_available_permits`, `current_queued_count`,
+    /// `current_waiting_count`, `total_successful_leases`,
+    /// `total_failed_leases`, `dropped_permits`, and the queue wait-time
+    /// metrics over all cached per-key limiters, giving a single dashboard
+    /// number for the whole fleet of partitions.
+    pub fn aggregate_statistics(&self) -> RateLimiterStatistics {
+        let snapshot: Vec<Arc<dyn RateLimiter>> = {
+            let limiters = self.limiters.lock();
+            limiters.values().map(|entry| entry.limiter.clone()).collect()
+        };
+
+        let mut available_permits = 0i64;
+        let mut queued_count = 0u32;
+        let mut waiting_count = 0u32;
+        let mut successful_leases = 0u64;
+        let mut failed_leases = 0u64;
+        let mut queued_lease_count = 0u64;
+        let mut total_queue_wait_time = Duration::ZERO;
+        let mut max_queue_wait_time = Duration::ZERO;
+        let mut dropped_permits = 0u64;
+
+        for limiter in snapshot {
+            let stats = limiter.get_statistics();
+            available_permits += stats.current_available_permits;
+            queued_count += stats.current_queued_count;
+            waiting_count += stats.current_waiting_count;
+            successful_leases += stats.total_successful_leases;
+            failed_leases += stats.total_failed_leases;
+            queued_lease_count += stats.queued_lease_count;
+            total_queue_wait_time += stats.total_queue_wait_time;
+            max_queue_wait_time = max_queue_wait_time.max(stats.max_queue_wait_time);
+            dropped_permits += stats.dropped_permits;
+        }
+
+        RateLimiterStatistics {
+            current_available_permits: available_permits,
+            current_queued_count: queued_count,
+            current_waiting_count: waiting_count,
+            total_successful_leases: successful_leases,
+            total_failed_leases: failed_leases,
+            queued_lease_count,
+            total_queue_wait_time,
+            max_queue_wait_time,
+            dropped_permits,
+        }
+    }
+
+    /// Synchronously evicts cached limiters that are both idle and empty.
+    ///
+    /// A partition is only removed when it hasn't been looked up via
+    /// `get_limiter` for at least `idle_time_limit` *and* its own
+    /// `idle_duration()` confirms it currently has no queued requests and
+    /// full permit availability - a limiter with leases outstanding or
+    /// callers queued always returns `None` from `idle_duration()` and is
+    /// left untouched, so a partition's budget is never silently reset out
+    /// from under an in-flight caller. Returns the number of partitions
+    /// removed so callers can emit metrics.
+    pub fn cleanup_idle(&self) -> usize {
+        Self::cleanup_idle_locked(&self.limiters, self.idle_time_limit)
+    }
+
+    /// Spawns a background task that calls `cleanup_idle` on a fixed
+    /// `interval` until this limiter is dropped.
+    ///
+    /// Use `cleanup_idle` directly instead when a test needs deterministic,
+    /// synchronous control over when eviction happens.
+    pub fn start_cleanup(&self, interval: Duration) {
+        let limiters = self.limiters.clone();
+        let idle_time_limit = self.idle_time_limit;
+        let cancel = self.cleanup_cancel.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        Self::cleanup_idle_locked(&limiters, idle_time_limit);
+                    }
+                    _ = cancel.cancelled() => break,
+                }
+            }
+        });
+    }
+
+    /// Shared scan-then-evict implementation backing both `cleanup_idle` and
+    /// the task spawned by `start_cleanup`.
+    ///
+    /// Collects removable keys under a single lock acquisition and then
+    /// removes them in one pass, rather than allocating a replacement map.
+    fn cleanup_idle_locked(
+        limiters: &Mutex<HashMap<TKey, CachedLimiter>>,
+        idle_time_limit: Duration,
+    ) -> usize {
+        let mut limiters = limiters.lock();
+
+        let stale_keys: Vec<TKey> = limiters
+            .iter()
+            .filter(|(_, entry)| {
+                entry.last_accessed.elapsed() >= idle_time_limit
+                    && entry.limiter.idle_duration().is_some()
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in &stale_keys {
+            limiters.remove(key);
+        }
+
+        stale_keys.len()
+    }
+}
+
+impl<TResource, TKey> Drop for PartitionedRateLimiter<TResource, TKey>
+where
+    TKey: Hash + Eq + Clone + Send + Sync + 'static,
+    TResource: Send + Sync,
+{
+    fn drop(&mut self) {
+        self.cleanup_cancel.cancel();
+    }
+}
+
+/// Stacks several `PartitionedRateLimiter`s over the same resource so that a
+/// lease is only granted once every limiter in the chain grants it for that
+/// resource (e.g. a per-user limiter AND a shared global limiter, both keyed
+/// off the same incoming request). Mirrors `ChainedRateLimiter`'s
+/// all-must-approve semantics, applied across partitioned limiters instead of
+/// plain `RateLimiter` trait objects.
+pub struct CombinedPartitionedRateLimiter<TResource, TKey>
+where
+    TKey: Hash + Eq + Clone + Send + Sync + 'static,
+    TResource: Send + Sync,
+{
+    limiters: Vec<Arc<PartitionedRateLimiter<TResource, TKey>>>,
+}
+
+impl<TResource, TKey> CombinedPartitionedRateLimiter<TResource, TKey>
+where
+    TKey: Hash + Eq + Clone + Send + Sync + 'static,
+    TResource: Send + Sync,
+{
+    /// Combines `limiters` into a single partitioned limiter requiring all of
+    /// them to grant a lease for a resource before one is returned.
+    ///
+    /// # Errors
+    ///
+    /// * `InvalidParameter` - If the limiters vector is empty
+    pub fn new(
+        limiters: Vec<Arc<PartitionedRateLimiter<TResource, TKey>>>,
+    ) -> Result<Self, RateLimitError> {
+        if limiters.is_empty() {
+            return Err(RateLimitError::InvalidParameter(
+                "Must provide at least 1 partitioned limiter".to_string(),
+            ));
+        }
+
+        Ok(Self { limiters })
+    }
+
+    /// Attempts to acquire a lease for `resource` from every limiter in the
+    /// chain, in order. If any limiter fails, permits already acquired from
+    /// earlier limiters are released and that failure is returned.
+    pub fn attempt_acquire(
+        &self,
+        resource: &TResource,
+        permit_count: u32,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        let mut acquired_leases = Vec::with_capacity(self.limiters.len());
+
+        for limiter in &self.limiters {
+            match limiter.attempt_acquire(resource, permit_count) {
+                Ok(lease) if lease.is_acquired() => acquired_leases.push(lease),
+                Ok(lease) => {
+                    drop(acquired_leases);
+                    return Ok(lease);
+                }
+                Err(e) => {
+                    drop(acquired_leases);
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(CombinedLease::create(acquired_leases))
+    }
+
+    /// Asynchronously acquires a lease for `resource` from every limiter in
+    /// the chain, in order. If any limiter fails, permits already acquired
+    /// from earlier limiters are released and that failure is returned.
+    pub async fn acquire_async(
+        &self,
+        resource: &TResource,
+        permit_count: u32,
+        cancellation_token: Option<CancellationToken>,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        let mut acquired_leases = Vec::with_capacity(self.limiters.len());
+
+        for limiter in &self.limiters {
+            match limiter
+                .acquire_async(resource, permit_count, cancellation_token.clone())
+                .await
+            {
+                Ok(lease) if lease.is_acquired() => acquired_leases.push(lease),
+                Ok(lease) => {
+                    drop(acquired_leases);
+                    return Ok(lease);
+                }
+                Err(e) => {
+                    drop(acquired_leases);
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(CombinedLease::create(acquired_leases))
+    }
+}
+
+/// Helper to create a combined lease that releases all inner leases when dropped.
+struct CombinedLease;
+
+impl CombinedLease {
+    /// Create a successful lease that will release all inner leases when dropped.
+    fn create(leases: Vec<RateLimitLease>) -> RateLimitLease {
+        RateLimitLease::success_with_cleanup(move || {
+            drop(leases);
+        })
+    }
 }
 
 /// Factory function for creating simple per-key token bucket limiters.
@@ -188,6 +672,8 @@ where
             queue_limit,
             queue_processing_order,
             auto_replenishment,
+            0,     // one_time_burst
+            false, // replenish_fractionally
         ).expect("Failed to create token bucket options");
 
         (
@@ -300,6 +786,7 @@ where
 {
     move |key: &TKey| {
         use crate::{SlidingWindowRateLimiter, SlidingWindowRateLimiterOptions};
+        use crate::utils::SystemClock;
 
         let options = SlidingWindowRateLimiterOptions {
             permit_limit,
@@ -308,6 +795,9 @@ where
             queue_limit,
             queue_processing_order,
             auto_replenishment,
+            max_queue_duration: None,
+            clock: Arc::new(SystemClock),
+            request_channel_capacity: 1024,
         };
 
         (
@@ -317,6 +807,40 @@ where
     }
 }
 
+/// Factory function for creating simple per-key multi-bucket limiters.
+///
+/// This is a convenience function for the common case of creating
+/// `MultiBucketRateLimiter`s with the same named bucket configuration for
+/// each partition key (e.g. a per-user "bytes" + "ops" budget pair).
+///
+/// # Example
+/// ```no_run
+/// use ratelimit::{PartitionedRateLimiter, create_per_key_multi_bucket, BucketConfig};
+/// use std::collections::HashMap;
+/// use std::time::Duration;
+///
+/// let mut buckets = HashMap::new();
+/// buckets.insert("bytes".to_string(), BucketConfig::new(1_000_000, 1_000_000, Duration::from_secs(1)).unwrap());
+/// buckets.insert("ops".to_string(), BucketConfig::new(100, 100, Duration::from_secs(1)).unwrap());
+///
+/// let limiter = PartitionedRateLimiter::new(create_per_key_multi_bucket::<String>(buckets));
+/// ```
+pub fn create_per_key_multi_bucket<TKey>(
+    buckets: HashMap<String, crate::limiters::multi_bucket::BucketConfig>,
+) -> impl Fn(&TKey) -> (TKey, Arc<dyn RateLimiter>) + Send + Sync + 'static
+where
+    TKey: Clone + Hash + Eq + Send + Sync + 'static,
+{
+    move |key: &TKey| {
+        use crate::limiters::multi_bucket::MultiBucketRateLimiter;
+
+        (
+            key.clone(),
+            Arc::new(MultiBucketRateLimiter::new(buckets.clone())) as Arc<dyn RateLimiter>,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -327,7 +851,7 @@ mod tests {
     fn test_partitioned_creates_separate_limiters() {
         // Create a partitioned limiter that gives each key 2 permits
         let limiter = PartitionedRateLimiter::new(|key: &String| {
-            let options = TokenBucketRateLimiterOptions::new(2, 2, Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false)
+            let options = TokenBucketRateLimiterOptions::new(2, 2, Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false, 0, false)
                 .expect("Failed to create options");
             (
                 key.clone(),
@@ -358,7 +882,7 @@ mod tests {
     #[test]
     fn test_partitioned_reuses_existing_limiters() {
         let limiter = PartitionedRateLimiter::new(|key: &String| {
-            let options = TokenBucketRateLimiterOptions::new(3, 3, Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false)
+            let options = TokenBucketRateLimiterOptions::new(3, 3, Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false, 0, false)
                 .expect("Failed to create options");
             (
                 key.clone(),
@@ -415,7 +939,7 @@ mod tests {
     #[test]
     fn test_clear() {
         let limiter = PartitionedRateLimiter::new(|key: &i32| {
-            let options = TokenBucketRateLimiterOptions::new(1, 1, Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false)
+            let options = TokenBucketRateLimiterOptions::new(1, 1, Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false, 0, false)
                 .expect("Failed to create options");
             (
                 *key,
@@ -439,10 +963,104 @@ mod tests {
         assert_eq!(limiter.partition_count(), 1);
     }
 
+    #[test]
+    fn test_aggregate_statistics_sums_partitions() {
+        let limiter = PartitionedRateLimiter::new(|key: &String| {
+            let options = TokenBucketRateLimiterOptions::new(2, 2, Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false, 0, false)
+                .expect("Failed to create options");
+            (
+                key.clone(),
+                Arc::new(TokenBucketRateLimiter::new(options).expect("Failed to create limiter")) as Arc<dyn RateLimiter>,
+            )
+        });
+
+        let _ = limiter.attempt_acquire(&"user1".to_string(), 1);
+        let _ = limiter.attempt_acquire(&"user2".to_string(), 1);
+
+        let stats = limiter.aggregate_statistics();
+        // 1 remaining token in each of 2 partitions
+        assert_eq!(stats.current_available_permits, 2);
+        assert_eq!(stats.total_successful_leases, 2);
+    }
+
+    #[test]
+    fn test_partition_keys_and_statistics_by_partition() {
+        let limiter = PartitionedRateLimiter::new(|key: &String| {
+            let options = TokenBucketRateLimiterOptions::new(2, 2, Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false, 0, false)
+                .expect("Failed to create options");
+            (
+                key.clone(),
+                Arc::new(TokenBucketRateLimiter::new(options).expect("Failed to create limiter")) as Arc<dyn RateLimiter>,
+            )
+        });
+
+        let _ = limiter.attempt_acquire(&"user1".to_string(), 1);
+        let _ = limiter.attempt_acquire(&"user2".to_string(), 2);
+
+        let mut keys = limiter.partition_keys();
+        keys.sort();
+        assert_eq!(keys, vec!["user1".to_string(), "user2".to_string()]);
+
+        let by_partition = limiter.statistics_by_partition();
+        assert_eq!(by_partition.len(), 2);
+        assert_eq!(by_partition["user1"].current_available_permits, 1);
+        assert_eq!(by_partition["user2"].current_available_permits, 0);
+    }
+
+    #[test]
+    fn test_cleanup_idle_removes_only_idle_empty_partitions() {
+        let limiter = PartitionedRateLimiter::with_idle_limit(
+            |key: &String| {
+                let options = TokenBucketRateLimiterOptions::new(1, 1, Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false, 0, false)
+                    .expect("Failed to create options");
+                (
+                    key.clone(),
+                    Arc::new(TokenBucketRateLimiter::new(options).expect("Failed to create limiter")) as Arc<dyn RateLimiter>,
+                )
+            },
+            Duration::ZERO,
+        );
+
+        // Idle partition - should be reaped immediately (idle_time_limit is zero)
+        let _ = limiter.attempt_acquire(&"idle".to_string(), 0);
+
+        // In-use partition - token is exhausted, so idle_duration() is None
+        let _lease = limiter.attempt_acquire(&"busy".to_string(), 1).unwrap();
+
+        assert_eq!(limiter.partition_count(), 2);
+
+        let removed = limiter.cleanup_idle();
+        assert_eq!(removed, 1);
+        assert_eq!(limiter.partition_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_start_cleanup_evicts_in_background() {
+        let limiter = PartitionedRateLimiter::with_idle_limit(
+            |key: &String| {
+                let options = TokenBucketRateLimiterOptions::new(1, 1, Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false, 0, false)
+                    .expect("Failed to create options");
+                (
+                    key.clone(),
+                    Arc::new(TokenBucketRateLimiter::new(options).expect("Failed to create limiter")) as Arc<dyn RateLimiter>,
+                )
+            },
+            Duration::ZERO,
+        );
+
+        let _ = limiter.attempt_acquire(&"idle".to_string(), 0);
+        assert_eq!(limiter.partition_count(), 1);
+
+        limiter.start_cleanup(Duration::from_millis(10));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert_eq!(limiter.partition_count(), 0);
+    }
+
     #[tokio::test]
     async fn test_async_acquire() {
         let limiter = PartitionedRateLimiter::new(|key: &String| {
-            let options = TokenBucketRateLimiterOptions::new(1, 1, Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false)
+            let options = TokenBucketRateLimiterOptions::new(1, 1, Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false, 0, false)
                 .expect("Failed to create options");
             (
                 key.clone(),
@@ -458,4 +1076,178 @@ mod tests {
         let lease2 = limiter.acquire_async(&"user2".to_string(), 1, None).await.unwrap();
         assert!(lease2.is_acquired());
     }
+
+    fn unlimited_overflow_limiter() -> Arc<dyn RateLimiter> {
+        let options = TokenBucketRateLimiterOptions::new(1_000, 1_000, Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false, 0, false)
+            .expect("Failed to create options");
+        Arc::new(TokenBucketRateLimiter::new(options).expect("Failed to create limiter"))
+    }
+
+    #[test]
+    fn test_hot_key_detected_and_redirected_to_overflow() {
+        let limiter = PartitionedRateLimiter::with_overflow(
+            |key: &String| {
+                let options = TokenBucketRateLimiterOptions::new(1, 1, Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false, 0, false)
+                    .expect("Failed to create options");
+                (
+                    key.clone(),
+                    Arc::new(TokenBucketRateLimiter::new(options).expect("Failed to create limiter")) as Arc<dyn RateLimiter>,
+                )
+            },
+            Duration::from_secs(10),
+            unlimited_overflow_limiter(),
+            2,
+            Duration::from_secs(60),
+        );
+
+        // First couple of requests stay under the threshold and hit the
+        // per-key limiter directly (which only has 1 permit).
+        assert!(!limiter.is_overflowing(&"hot".to_string()));
+        let lease1 = limiter.attempt_acquire(&"hot".to_string(), 1).unwrap();
+        assert!(lease1.is_acquired());
+        let lease2 = limiter.attempt_acquire(&"hot".to_string(), 1).unwrap();
+        assert!(!lease2.is_acquired()); // per-key limiter is exhausted
+
+        // A third request crosses the threshold (> 2) and should be routed
+        // to the (effectively unlimited) overflow limiter instead.
+        let lease3 = limiter.attempt_acquire(&"hot".to_string(), 1).unwrap();
+        assert!(lease3.is_acquired());
+        assert!(limiter.is_overflowing(&"hot".to_string()));
+        assert_eq!(limiter.overflow_hit_count(), 1);
+    }
+
+    #[test]
+    fn test_get_statistics_does_not_drive_keys_into_overflow() {
+        let limiter = PartitionedRateLimiter::with_overflow(
+            |key: &String| {
+                let options = TokenBucketRateLimiterOptions::new(1, 1, Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false, 0, false)
+                    .expect("Failed to create options");
+                (
+                    key.clone(),
+                    Arc::new(TokenBucketRateLimiter::new(options).expect("Failed to create limiter")) as Arc<dyn RateLimiter>,
+                )
+            },
+            Duration::from_secs(10),
+            unlimited_overflow_limiter(),
+            2,
+            Duration::from_secs(60),
+        );
+
+        // A metrics scraper polling get_statistics well more than the
+        // threshold number of times must not itself push the key into
+        // overflow or bump overflow_hit_count - only attempt_acquire and
+        // acquire_async may do that.
+        for _ in 0..10 {
+            let _ = limiter.get_statistics(&"scraped".to_string());
+        }
+
+        assert!(!limiter.is_overflowing(&"scraped".to_string()));
+        assert_eq!(limiter.overflow_hit_count(), 0);
+    }
+
+    #[test]
+    fn test_forced_overflow_key_bypasses_detection() {
+        let limiter = PartitionedRateLimiter::with_overflow(
+            |key: &String| {
+                let options = TokenBucketRateLimiterOptions::new(5, 5, Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false, 0, false)
+                    .expect("Failed to create options");
+                (
+                    key.clone(),
+                    Arc::new(TokenBucketRateLimiter::new(options).expect("Failed to create limiter")) as Arc<dyn RateLimiter>,
+                )
+            },
+            Duration::from_secs(10),
+            unlimited_overflow_limiter(),
+            100,
+            Duration::from_secs(60),
+        );
+
+        limiter.add_forced_overflow_key("celebrity".to_string());
+        assert!(limiter.is_overflowing(&"celebrity".to_string()));
+
+        let lease = limiter.attempt_acquire(&"celebrity".to_string(), 1).unwrap();
+        assert!(lease.is_acquired());
+        assert_eq!(limiter.overflow_hit_count(), 1);
+
+        // A normal key stays on its own per-key limiter.
+        assert!(!limiter.is_overflowing(&"regular".to_string()));
+
+        limiter.remove_forced_overflow_key(&"celebrity".to_string());
+        assert!(!limiter.is_overflowing(&"celebrity".to_string()));
+    }
+
+    fn per_key_limiter(limit: u32) -> PartitionedRateLimiter<String, String> {
+        PartitionedRateLimiter::new(move |key: &String| {
+            let options = TokenBucketRateLimiterOptions::new(
+                limit,
+                limit,
+                Duration::from_secs(60),
+                0,
+                QueueProcessingOrder::OldestFirst,
+                false,
+                0,
+                false,
+            )
+            .expect("Failed to create options");
+            (
+                key.clone(),
+                Arc::new(TokenBucketRateLimiter::new(options).expect("Failed to create limiter")) as Arc<dyn RateLimiter>,
+            )
+        })
+    }
+
+    #[test]
+    fn test_combined_empty_limiters_error() {
+        let result: Result<CombinedPartitionedRateLimiter<String, String>, _> =
+            CombinedPartitionedRateLimiter::new(vec![]);
+        assert!(matches!(result, Err(RateLimitError::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_combined_requires_every_limiter_to_grant() {
+        // Concurrency permits go back on lease drop (token bucket tokens
+        // don't), so the release below is observable.
+        let per_user = Arc::new(PartitionedRateLimiter::new(
+            create_per_key_concurrency::<String>(5, 0, QueueProcessingOrder::OldestFirst),
+        ));
+        let global = Arc::new(per_key_limiter(1));
+
+        // Exhaust the global limiter's only permit for "shared" up front.
+        let _lease = global.attempt_acquire(&"shared".to_string(), 1).unwrap();
+
+        let combined =
+            CombinedPartitionedRateLimiter::new(vec![per_user.clone(), global.clone()]).unwrap();
+
+        let lease = combined.attempt_acquire(&"shared".to_string(), 1).unwrap();
+        assert!(!lease.is_acquired());
+
+        // The per-user limiter's permit should have been released back.
+        let stats = per_user.get_statistics(&"shared".to_string());
+        assert_eq!(stats.current_available_permits, 5);
+    }
+
+    #[test]
+    fn test_combined_succeeds_when_all_grant() {
+        let per_user = Arc::new(per_key_limiter(5));
+        let global = Arc::new(per_key_limiter(10));
+
+        let combined = CombinedPartitionedRateLimiter::new(vec![per_user, global]).unwrap();
+
+        let lease = combined.attempt_acquire(&"user1".to_string(), 1).unwrap();
+        assert!(lease.is_acquired());
+    }
+
+    #[tokio::test]
+    async fn test_combined_acquire_async() {
+        let per_user = Arc::new(per_key_limiter(5));
+        let global = Arc::new(per_key_limiter(10));
+
+        let combined = CombinedPartitionedRateLimiter::new(vec![per_user, global]).unwrap();
+
+        let lease = combined
+            .acquire_async(&"user1".to_string(), 1, None)
+            .await
+            .unwrap();
+        assert!(lease.is_acquired());
+    }
 }
\ No newline at end of file
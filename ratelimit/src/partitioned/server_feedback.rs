@@ -0,0 +1,206 @@
+use crate::limiters::{RateLimitHeaders, ServerFeedbackLimiter};
+use crate::{RateLimitError, RateLimitLease, RateLimiter, RateLimiterStatistics};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+/// Type alias for the partitioner function that maps resources to partition keys and limiters.
+type PartitionerFn<TResource, TKey> = dyn Fn(&TResource) -> (TKey, Arc<dyn RateLimiter>) + Send + Sync;
+
+/// A `PartitionedRateLimiter`-style adapter whose per-key limiters can be
+/// reconciled against a downstream API's own advertised rate limit state.
+///
+/// Each partition key gets its own `ServerFeedbackLimiter`, wrapping
+/// whatever limiter the `partitioner` function builds for it. Besides the
+/// usual `attempt_acquire`/`acquire_async`/`get_statistics`, this also
+/// exposes `update_from_headers(key, headers)` so a caller that just got a
+/// 200 or 429 back from a per-key-scoped downstream dependency can feed its
+/// response headers straight back into that key's partition, tracking
+/// multiple overlapping server-side buckets per key and avoiding 429s by
+/// trusting authoritative server counts rather than only the local
+/// estimate.
+///
+/// # Example
+/// ```no_run
+/// use ratelimit::partitioned::{ServerFeedbackPartitionedLimiter, RateLimitHeaders};
+/// use ratelimit::{TokenBucketRateLimiter, TokenBucketRateLimiterOptions, RateLimiter, QueueProcessingOrder};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let limiter = ServerFeedbackPartitionedLimiter::new(|user_id: &String| {
+///     let options = TokenBucketRateLimiterOptions::new(100, 100, Duration::from_secs(60), 0, QueueProcessingOrder::OldestFirst, false, 0, false).unwrap();
+///     (user_id.clone(), Arc::new(TokenBucketRateLimiter::new(options).unwrap()) as Arc<dyn RateLimiter>)
+/// });
+///
+/// let lease = limiter.attempt_acquire(&"user1".to_string(), 1).unwrap();
+/// assert!(lease.is_acquired());
+///
+/// // The downstream API for "user1" reports only 1 request left in its window.
+/// limiter.update_from_headers(&"user1".to_string(), RateLimitHeaders { remaining: Some(1), ..Default::default() });
+/// assert_eq!(limiter.get_statistics(&"user1".to_string()).current_available_permits, 1);
+/// ```
+pub struct ServerFeedbackPartitionedLimiter<TResource, TKey>
+where
+    TKey: Hash + Eq + Clone + Send + Sync + 'static,
+    TResource: Send + Sync,
+{
+    limiters: Mutex<HashMap<TKey, Arc<ServerFeedbackLimiter<Arc<dyn RateLimiter>>>>>,
+    partitioner: Arc<PartitionerFn<TResource, TKey>>,
+}
+
+impl<TResource, TKey> ServerFeedbackPartitionedLimiter<TResource, TKey>
+where
+    TKey: Hash + Eq + Clone + Send + Sync + 'static,
+    TResource: Send + Sync,
+{
+    /// Creates a new server-feedback partitioned limiter.
+    ///
+    /// # Arguments
+    /// - `partitioner`: Function that maps resources to partition keys and creates the
+    ///   limiter to wrap for new keys, exactly as for `PartitionedRateLimiter::new`.
+    pub fn new<F>(partitioner: F) -> Self
+    where
+        F: Fn(&TResource) -> (TKey, Arc<dyn RateLimiter>) + Send + Sync + 'static,
+    {
+        Self {
+            limiters: Mutex::new(HashMap::new()),
+            partitioner: Arc::new(partitioner),
+        }
+    }
+
+    /// Gets or creates the `ServerFeedbackLimiter` for the given resource's
+    /// partition key.
+    fn get_limiter(&self, resource: &TResource) -> Arc<ServerFeedbackLimiter<Arc<dyn RateLimiter>>> {
+        let (key, new_inner) = (self.partitioner)(resource);
+
+        let mut limiters = self.limiters.lock();
+        limiters
+            .entry(key)
+            .or_insert_with(|| Arc::new(ServerFeedbackLimiter::new(new_inner)))
+            .clone()
+    }
+
+    /// Attempts to acquire a lease for the given resource.
+    pub fn attempt_acquire(&self, resource: &TResource, permit_count: u32) -> Result<RateLimitLease, RateLimitError> {
+        self.get_limiter(resource).attempt_acquire(permit_count)
+    }
+
+    /// Asynchronously acquires a lease for the given resource.
+    pub async fn acquire_async(
+        &self,
+        resource: &TResource,
+        permit_count: u32,
+        cancellation_token: Option<CancellationToken>,
+    ) -> Result<RateLimitLease, RateLimitError> {
+        self.get_limiter(resource)
+            .acquire_async(permit_count, cancellation_token)
+            .await
+    }
+
+    /// Gets statistics for the given resource's partition, with
+    /// `current_available_permits` already reconciled against any
+    /// server-reported ceiling for that key.
+    pub fn get_statistics(&self, resource: &TResource) -> RateLimiterStatistics {
+        self.get_limiter(resource).get_statistics()
+    }
+
+    /// Reconciles the partition for `key` against a parsed server response.
+    ///
+    /// Returns `false` without effect if no partition has been created for
+    /// `key` yet (i.e. `attempt_acquire`/`acquire_async` hasn't been called
+    /// for a resource mapping to it) - there is nothing to reconcile until a
+    /// limiter exists to reconcile against. See
+    /// `ServerFeedbackLimiter::update_from_headers` for how `headers` is
+    /// applied.
+    pub fn update_from_headers(&self, key: &TKey, headers: RateLimitHeaders) -> bool {
+        let limiters = self.limiters.lock();
+        match limiters.get(key) {
+            Some(limiter) => {
+                limiter.update_from_headers(headers);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Gets the number of active partitions.
+    pub fn partition_count(&self) -> usize {
+        self.limiters.lock().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TokenBucketRateLimiter, TokenBucketRateLimiterOptions};
+    use crate::QueueProcessingOrder;
+    use std::time::Duration;
+
+    fn limiter() -> ServerFeedbackPartitionedLimiter<String, String> {
+        ServerFeedbackPartitionedLimiter::new(|key: &String| {
+            let options = TokenBucketRateLimiterOptions::new(
+                10,
+                10,
+                Duration::from_secs(60),
+                0,
+                QueueProcessingOrder::OldestFirst,
+                false,
+                0,
+                false,
+            )
+            .expect("Failed to create options");
+            (
+                key.clone(),
+                Arc::new(TokenBucketRateLimiter::new(options).expect("Failed to create limiter")) as Arc<dyn RateLimiter>,
+            )
+        })
+    }
+
+    #[test]
+    fn test_update_from_headers_shrinks_the_right_partition() {
+        let limiter = limiter();
+
+        let _ = limiter.attempt_acquire(&"user1".to_string(), 1);
+        let _ = limiter.attempt_acquire(&"user2".to_string(), 1);
+
+        let updated = limiter.update_from_headers(
+            &"user1".to_string(),
+            RateLimitHeaders {
+                remaining: Some(1),
+                ..Default::default()
+            },
+        );
+        assert!(updated);
+
+        assert_eq!(limiter.get_statistics(&"user1".to_string()).current_available_permits, 1);
+        // user2's partition is untouched by user1's feedback.
+        assert_eq!(limiter.get_statistics(&"user2".to_string()).current_available_permits, 9);
+    }
+
+    #[test]
+    fn test_update_from_headers_is_a_no_op_for_unknown_key() {
+        let limiter = limiter();
+        let updated = limiter.update_from_headers(&"never-seen".to_string(), RateLimitHeaders::default());
+        assert!(!updated);
+        assert_eq!(limiter.partition_count(), 0);
+    }
+
+    #[test]
+    fn test_retry_after_blocks_until_it_elapses() {
+        let limiter = limiter();
+        let _ = limiter.attempt_acquire(&"user1".to_string(), 1);
+
+        limiter.update_from_headers(
+            &"user1".to_string(),
+            RateLimitHeaders {
+                retry_after: Some(Duration::from_secs(30)),
+                ..Default::default()
+            },
+        );
+
+        let lease = limiter.attempt_acquire(&"user1".to_string(), 1).unwrap();
+        assert!(!lease.is_acquired());
+    }
+}
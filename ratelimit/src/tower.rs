@@ -0,0 +1,109 @@
+//! Optional [`tower`](https://docs.rs/tower) `Service`/`Layer` integration.
+//!
+//! Enable the `tower` feature to wrap any `RateLimiter` as drop-in middleware
+//! for `tower`-based servers and clients, instead of only using it as a
+//! standalone primitive.
+
+use crate::core::{RateLimitError, RateLimiter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// A [`tower::Layer`] that wraps an inner service with a [`RateLimiter`].
+pub struct RateLimitLayer<L: ?Sized> {
+    limiter: Arc<L>,
+}
+
+impl<L: ?Sized> RateLimitLayer<L> {
+    /// Create a new layer backed by the given limiter.
+    pub fn new(limiter: Arc<L>) -> Self {
+        Self { limiter }
+    }
+}
+
+impl<L: ?Sized> Clone for RateLimitLayer<L> {
+    fn clone(&self) -> Self {
+        Self {
+            limiter: Arc::clone(&self.limiter),
+        }
+    }
+}
+
+impl<S, L> Layer<S> for RateLimitLayer<L>
+where
+    L: ?Sized,
+{
+    type Service = RateLimit<S, L>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimit {
+            inner,
+            limiter: Arc::clone(&self.limiter),
+        }
+    }
+}
+
+/// A [`tower::Service`] that acquires a single permit from a [`RateLimiter`]
+/// before calling through to the inner service, and releases it once the
+/// inner service's future completes.
+///
+/// Acquisition happens in `call` (rather than `poll_ready`) since the
+/// `RateLimiter` trait has no notion of "reserve now, commit later" that
+/// would let us hold a permit across the two calls.
+pub struct RateLimit<S, L: ?Sized> {
+    inner: S,
+    limiter: Arc<L>,
+}
+
+impl<S: Clone, L: ?Sized> Clone for RateLimit<S, L> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            limiter: Arc::clone(&self.limiter),
+        }
+    }
+}
+
+impl<S, L, Request> Service<Request> for RateLimit<S, L>
+where
+    S: Service<Request> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: std::error::Error + Send + Sync + 'static,
+    L: RateLimiter + ?Sized + 'static,
+    Request: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner
+            .poll_ready(cx)
+            .map_err(|e| Box::new(e) as Self::Error)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let limiter = Arc::clone(&self.limiter);
+        // tower::Service::call requires `&mut self` but returns a future that
+        // must not borrow `self`, so the common pattern is to ready a clone
+        // of the inner service and move it into the returned future.
+        let mut inner = self.inner.clone();
+
+        Box::pin(async move {
+            let lease = limiter
+                .acquire_async(1, None)
+                .await
+                .map_err(|e| Box::new(e) as Self::Error)?;
+
+            if !lease.is_acquired() {
+                return Err(Box::new(RateLimitError::QueueLimitExceeded) as Self::Error);
+            }
+
+            let response = inner.call(req).await.map_err(|e| Box::new(e) as Self::Error)?;
+            drop(lease);
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,5 @@
+//! Small supporting abstractions shared across limiters.
+
+pub mod clock;
+
+pub use clock::{CachedClock, Clock, ManualClock, SystemClock, TokioClock};